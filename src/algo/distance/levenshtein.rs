@@ -1,35 +1,331 @@
+use num::Zero;
+use std::{collections::HashMap, hash::Hash, ops::Add};
+
+/// cost of each edit operation, keeping equal element always costs zero
+pub trait EditCost<T> {
+    type Cost: Copy + Ord + Add<Output = Self::Cost> + Zero;
+    /// cost of inserting y
+    fn insert(&self, y: &T) -> Self::Cost;
+    /// cost of deleting x
+    fn delete(&self, x: &T) -> Self::Cost;
+    /// cost of substituting x with y, called only when x != y
+    fn substitute(&self, x: &T, y: &T) -> Self::Cost;
+    /// cost of swapping adjacent x and y, called only when x != y, same as substitution by default
+    fn transpose(&self, x: &T, y: &T) -> Self::Cost {
+        self.substitute(x, y)
+    }
+}
+
+/// every operation costs one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UnitCost;
+impl<T> EditCost<T> for UnitCost {
+    type Cost = usize;
+    fn insert(&self, _: &T) -> usize {
+        1
+    }
+    fn delete(&self, _: &T) -> usize {
+        1
+    }
+    fn substitute(&self, _: &T, _: &T) -> usize {
+        1
+    }
+}
+
+/// costs given by closures (insert, delete, substitute)
+impl<T, C, I, D, S> EditCost<T> for (I, D, S)
+where
+    C: Copy + Ord + Add<Output = C> + Zero,
+    I: Fn(&T) -> C,
+    D: Fn(&T) -> C,
+    S: Fn(&T, &T) -> C,
+{
+    type Cost = C;
+    fn insert(&self, y: &T) -> C {
+        (self.0)(y)
+    }
+    fn delete(&self, x: &T) -> C {
+        (self.1)(x)
+    }
+    fn substitute(&self, x: &T, y: &T) -> C {
+        (self.2)(x, y)
+    }
+}
+
+/// operation of edit script, indices point to the original sequences a and b
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOperation {
+    /// a[i] is kept as b[j]
+    Keep(usize, usize),
+    /// a[i] is replaced with b[j]
+    Substitute(usize, usize),
+    /// a[i] is deleted
+    Delete(usize),
+    /// b[j] is inserted
+    Insert(usize),
+    /// a[i], a[i + 1] are swapped into b[j], b[j + 1]
+    Transpose(usize, usize),
+}
+
 /// **O(nm)**, calculate edit distance between sequence a and sequence b
 pub fn levenshtein_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    weighted_edit_distance(a, b, &UnitCost)
+}
+
+/// **O(nm)**, dp[i][j] is edit distance between a[..i] and b[..j]
+pub fn edit_distance_table<T, C>(a: &[T], b: &[T], cost: &C) -> Vec<Vec<C::Cost>>
+where
+    T: PartialEq,
+    C: EditCost<T>,
+{
+    fill_table(a, b, cost, false)
+}
+
+/// **O(nm)**, calculate edit distance with weighted costs
+pub fn weighted_edit_distance<T, C>(a: &[T], b: &[T], cost: &C) -> C::Cost
+where
+    T: PartialEq,
+    C: EditCost<T>,
+{
+    fill_table(a, b, cost, false)[a.len()][b.len()]
+}
+
+/// **O(nm)**, edit distance and one of the optimal edit scripts that transform a into b
+pub fn edit_script<T, C>(a: &[T], b: &[T], cost: &C) -> (C::Cost, Vec<EditOperation>)
+where
+    T: PartialEq,
+    C: EditCost<T>,
+{
+    let dp = fill_table(a, b, cost, false);
+    (dp[a.len()][b.len()], backtrack(a, b, cost, &dp, false))
+}
+
+/// **O(nm)**, optimal string alignment distance (restricted Damerau-Levenshtein distance),
+/// adjacent transposition is allowed but the transposed elements are not edited again
+pub fn osa_distance<T, C>(a: &[T], b: &[T], cost: &C) -> C::Cost
+where
+    T: PartialEq,
+    C: EditCost<T>,
+{
+    fill_table(a, b, cost, true)[a.len()][b.len()]
+}
+
+/// **O(nm)**, optimal string alignment distance and one of the optimal edit scripts
+pub fn osa_edit_script<T, C>(a: &[T], b: &[T], cost: &C) -> (C::Cost, Vec<EditOperation>)
+where
+    T: PartialEq,
+    C: EditCost<T>,
+{
+    let dp = fill_table(a, b, cost, true);
+    (dp[a.len()][b.len()], backtrack(a, b, cost, &dp, true))
+}
+
+/// **O(nm)**, diff based on longest common subsequence, which consist of only keep, delete and insert
+pub fn lcs_diff<T: PartialEq>(a: &[T], b: &[T]) -> Vec<EditOperation> {
+    // substitution is never cheaper than deletion and insertion, and backtrack prefer them
+    let cost = (|_: &T| 1, |_: &T| 1, |_: &T, _: &T| 2);
+    edit_script(a, b, &cost).1
+}
+
+/// **O((n + m)k)**, edit distance if it is at most k, else None (Ukkonen's banded dp)
+pub fn levenshtein_within<T: PartialEq>(a: &[T], b: &[T], k: usize) -> Option<usize> {
     let (n, m) = (a.len(), b.len());
-    if n == 0 || m == 0 {
-        return std::cmp::max(n, m);
-    }
-    // dp[i][j]: edit distance between a[0..=i] and b[0..=j]
-    let mut dp = vec![vec![0; m]; n];
-    dp[0][0] = if a[0] == b[0] { 0 } else { 1 };
-    for i in 1..n {
-        let condition = dp[i - 1][0] == i && a[i] == b[0];
-        dp[i][0] = dp[i - 1][0] + if condition { 0 } else { 1 };
-    }
-    for j in 1..m {
-        let condition = dp[0][j - 1] == j && a[0] == b[j];
-        dp[0][j] = dp[0][j - 1] + if condition { 0 } else { 1 };
-    }
-    for i in 1..n {
-        for j in 1..m {
-            let add_a = dp[i - 1][j] + 1;
-            let add_b = dp[i][j - 1] + 1;
-            let modify = dp[i - 1][j - 1] + if a[i] == b[j] { 0 } else { 1 };
-            dp[i][j] = std::cmp::min(add_a, add_b).min(modify);
+    if n.max(m) - n.min(m) > k {
+        return None;
+    }
+    // distance never exceeds max(n, m), so larger k only widens the band beyond the table
+    let k = k.min(n.max(m));
+    // only cells of |i - j| <= k are calculated, cells just outside the band are k + 1 (= infinity)
+    let infinity = k + 1;
+    let mut prev: Vec<_> = (0..=m).map(|j| j.min(infinity)).collect();
+    let mut current = vec![infinity; m + 1];
+    for i in 1..=n {
+        let (lo, hi) = (i.saturating_sub(k), (i + k).min(m));
+        if lo == 0 {
+            current[0] = i;
+        } else {
+            current[lo - 1] = infinity;
+        }
+        let mut row_min = if lo == 0 { i } else { infinity };
+        for j in lo.max(1)..=hi {
+            let modify = prev[j - 1] + if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (prev[j] + 1).min(current[j - 1] + 1).min(modify).min(infinity);
+            row_min = row_min.min(current[j]);
+        }
+        if hi < m {
+            current[hi + 1] = infinity;
         }
+        if row_min > k {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut current);
+    }
+    Some(prev[m]).filter(|&distance| distance <= k)
+}
+
+/// **O(nm / 64)**, edit distance by Myers' bit-parallel algorithm
+pub fn myers_distance<T: Eq + Hash>(a: &[T], b: &[T]) -> usize {
+    // shorter one is encoded into bit vectors
+    let (pattern, text) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let n = pattern.len();
+    if n == 0 {
+        return text.len();
+    }
+    let blocks = n.div_ceil(64);
+    let mut peq: HashMap<&T, Vec<u64>> = HashMap::new();
+    for (i, x) in pattern.iter().enumerate() {
+        peq.entry(x).or_insert_with(|| vec![0; blocks])[i / 64] |= 1 << (i % 64);
     }
-    dp[n - 1][m - 1]
+    let zeros = vec![0; blocks];
+    // vertical delta of each row is +1 (pv) or -1 (mv) or 0
+    let (mut pv, mut mv) = (vec![!0u64; blocks], vec![0u64; blocks]);
+    let mut score = n;
+    for x in text {
+        let eq = peq.get(x).unwrap_or(&zeros);
+        // horizontal delta of the top row is always +1
+        let mut h_in = 1i8;
+        for block in 0..blocks {
+            let high = if block + 1 == blocks { 1 << ((n - 1) % 64) } else { 1 << 63 };
+            let (p, m) = (pv[block], mv[block]);
+            let mut e = eq[block];
+            let xv = e | m;
+            if h_in < 0 {
+                e |= 1;
+            }
+            let xh = ((e & p).wrapping_add(p) ^ p) | e;
+            let (mut ph, mut mh) = (m | !(xh | p), p & xh);
+            let h_out = if ph & high != 0 {
+                1
+            } else if mh & high != 0 {
+                -1
+            } else {
+                0
+            };
+            ph <<= 1;
+            mh <<= 1;
+            if h_in < 0 {
+                mh |= 1;
+            } else if h_in > 0 {
+                ph |= 1;
+            }
+            pv[block] = mh | !(xv | ph);
+            mv[block] = ph & xv;
+            h_in = h_out;
+        }
+        // h_in is now horizontal delta of the bottom row
+        score = (score as i64 + h_in as i64) as usize;
+    }
+    score
+}
+
+/// **O(1)**, can a[i - 2..i] be transposed into b[j - 2..j], swapping equal elements is not transposition
+fn transposable<T: PartialEq>(a: &[T], b: &[T], i: usize, j: usize) -> bool {
+    i >= 2 && j >= 2 && a[i - 1] != a[i - 2] && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1]
+}
+
+/// **O(nm)**, fill (n + 1) * (m + 1) dp table
+fn fill_table<T, C>(a: &[T], b: &[T], cost: &C, transposition: bool) -> Vec<Vec<C::Cost>>
+where
+    T: PartialEq,
+    C: EditCost<T>,
+{
+    let (n, m) = (a.len(), b.len());
+    let mut dp = vec![vec![C::Cost::zero(); m + 1]; n + 1];
+    for i in 1..=n {
+        dp[i][0] = dp[i - 1][0] + cost.delete(&a[i - 1]);
+    }
+    for j in 1..=m {
+        dp[0][j] = dp[0][j - 1] + cost.insert(&b[j - 1]);
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let delete = dp[i - 1][j] + cost.delete(&a[i - 1]);
+            let insert = dp[i][j - 1] + cost.insert(&b[j - 1]);
+            let modify = if a[i - 1] == b[j - 1] {
+                dp[i - 1][j - 1]
+            } else {
+                dp[i - 1][j - 1] + cost.substitute(&a[i - 1], &b[j - 1])
+            };
+            dp[i][j] = delete.min(insert).min(modify);
+            if transposition && transposable(a, b, i, j) {
+                let transpose = dp[i - 2][j - 2] + cost.transpose(&a[i - 2], &a[i - 1]);
+                dp[i][j] = dp[i][j].min(transpose);
+            }
+        }
+    }
+    dp
+}
+
+/// **O(n + m)**, restore edit script from filled dp table
+fn backtrack<T, C>(
+    a: &[T],
+    b: &[T],
+    cost: &C,
+    dp: &[Vec<C::Cost>],
+    transposition: bool,
+) -> Vec<EditOperation>
+where
+    T: PartialEq,
+    C: EditCost<T>,
+{
+    let (mut i, mut j) = (a.len(), b.len());
+    let mut script = Vec::new();
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && a[i - 1] == b[j - 1] && dp[i][j] == dp[i - 1][j - 1] {
+            script.push(EditOperation::Keep(i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && dp[i][j] == dp[i - 1][j] + cost.delete(&a[i - 1]) {
+            script.push(EditOperation::Delete(i - 1));
+            i -= 1;
+        } else if j > 0 && dp[i][j] == dp[i][j - 1] + cost.insert(&b[j - 1]) {
+            script.push(EditOperation::Insert(j - 1));
+            j -= 1;
+        } else if i > 0
+            && j > 0
+            && a[i - 1] != b[j - 1]
+            && dp[i][j] == dp[i - 1][j - 1] + cost.substitute(&a[i - 1], &b[j - 1])
+        {
+            script.push(EditOperation::Substitute(i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else {
+            assert!(transposition && transposable(a, b, i, j));
+            script.push(EditOperation::Transpose(i - 2, j - 2));
+            i -= 2;
+            j -= 2;
+        }
+    }
+    script.reverse();
+    script
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// apply edit script to a and check that it produce b
+    fn apply<T: PartialEq + Clone>(a: &[T], b: &[T], script: &[EditOperation]) -> Vec<T> {
+        let mut result = Vec::new();
+        for &operation in script {
+            match operation {
+                EditOperation::Keep(i, j) => {
+                    assert!(a[i] == b[j]);
+                    result.push(a[i].clone());
+                }
+                EditOperation::Substitute(_, j) | EditOperation::Insert(j) => {
+                    result.push(b[j].clone());
+                }
+                EditOperation::Delete(_) => (),
+                EditOperation::Transpose(i, _) => {
+                    result.push(a[i + 1].clone());
+                    result.push(a[i].clone());
+                }
+            }
+        }
+        result
+    }
+
     #[test]
     fn test_levenshtein1() {
         let a: Vec<_> = "xxy".chars().collect();
@@ -61,10 +357,159 @@ mod tests {
     #[test]
     fn test_levenshtein_bound() {
         assert_eq!(levenshtein_distance(&[], &["r", "u", "s", "t"]), 4);
-        assert_eq!(
-            levenshtein_distance(&['p', 'y', 't', 'h', 'o', 'n'], &[]),
-            6
-        );
+        assert_eq!(levenshtein_distance(&['p', 'y', 't', 'h', 'o', 'n'], &[]), 6);
         assert_eq!(levenshtein_distance(&vec![0; 0], &[0; 0]), 0);
     }
+
+    #[test]
+    fn test_edit_script() {
+        let a: Vec<_> = "kitten".chars().collect();
+        let b: Vec<_> = "sitting".chars().collect();
+        let (distance, script) = edit_script(&a, &b, &UnitCost);
+        assert_eq!(distance, 3);
+        assert_eq!(apply(&a, &b, &script), b);
+        assert_eq!(script[0], EditOperation::Substitute(0, 0));
+        assert_eq!(script.last(), Some(&EditOperation::Insert(6)));
+        let (distance, script) = edit_script(&a, &[], &UnitCost);
+        assert_eq!(distance, 6);
+        assert_eq!(script, (0..6).map(EditOperation::Delete).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_weighted() {
+        let a: Vec<_> = "abc".chars().collect();
+        let b: Vec<_> = "adc".chars().collect();
+        // substitution is more expensive than deletion and insertion
+        let cost = (|_: &char| 1, |_: &char| 1, |_: &char, _: &char| 3);
+        let (distance, script) = edit_script(&a, &b, &cost);
+        assert_eq!(distance, 2);
+        assert_eq!(apply(&a, &b, &script), b);
+        assert!(script.iter().all(|op| !matches!(op, EditOperation::Substitute(_, _))));
+        // deletion is free
+        let cost = (|_: &char| 10, |_: &char| 0, |_: &char, _: &char| 10);
+        assert_eq!(weighted_edit_distance(&a, &b, &cost), 10);
+        assert_eq!(weighted_edit_distance(&a, &a[..1], &cost), 0);
+        let dp = edit_distance_table(&a, &b, &UnitCost);
+        assert_eq!(dp[3][3], 1);
+        assert_eq!(dp[2][2], 1);
+        assert_eq!(dp[3][0], 3);
+    }
+
+    #[test]
+    fn test_osa() {
+        let a: Vec<_> = "ca".chars().collect();
+        let b: Vec<_> = "ac".chars().collect();
+        assert_eq!(levenshtein_distance(&a, &b), 2);
+        assert_eq!(osa_distance(&a, &b, &UnitCost), 1);
+        // optimal string alignment can not edit transposed substring again
+        let b: Vec<_> = "abc".chars().collect();
+        assert_eq!(osa_distance(&a, &b, &UnitCost), 3);
+        let a: Vec<_> = "abcdef".chars().collect();
+        let b: Vec<_> = "bacdfe".chars().collect();
+        let (distance, script) = osa_edit_script(&a, &b, &UnitCost);
+        assert_eq!(distance, 2);
+        assert_eq!(apply(&a, &b, &script), b);
+        assert_eq!(script[0], EditOperation::Transpose(0, 0));
+        // equal elements are never substituted nor transposed
+        let strict = (
+            |_: &char| 1,
+            |_: &char| 1,
+            |x: &char, y: &char| {
+                assert_ne!(x, y);
+                1
+            },
+        );
+        let a: Vec<_> = "aab".chars().collect();
+        assert_eq!(osa_distance(&a, &a, &strict), 0);
+        let b: Vec<_> = "aba".chars().collect();
+        assert_eq!(osa_distance(&a, &b, &strict), 1);
+    }
+
+    #[test]
+    fn test_lcs_diff() {
+        let a: Vec<_> = "ABCBDAB".chars().collect();
+        let b: Vec<_> = "BDCABA".chars().collect();
+        let script = lcs_diff(&a, &b);
+        assert_eq!(apply(&a, &b, &script), b);
+        let keeps = script.iter().filter(|op| matches!(op, EditOperation::Keep(_, _))).count();
+        assert_eq!(keeps, 4);
+        assert_eq!(script.len(), a.len() + b.len() - keeps);
+    }
+
+    #[test]
+    fn test_levenshtein_within() {
+        let a: Vec<_> = "kitten".chars().collect();
+        let b: Vec<_> = "sitting".chars().collect();
+        assert_eq!(levenshtein_within(&a, &b, 3), Some(3));
+        assert_eq!(levenshtein_within(&a, &b, 10), Some(3));
+        assert_eq!(levenshtein_within(&a, &b, 2), None);
+        assert_eq!(levenshtein_within(&a, &b[..1], 4), None);
+        assert_eq!(levenshtein_within(&a, &a, 0), Some(0));
+        assert_eq!(levenshtein_within(&[], &b, 7), Some(7));
+        assert_eq!(levenshtein_within(&a, &b, usize::MAX), Some(3));
+        assert_eq!(levenshtein_within(&[], &b, usize::MAX), Some(7));
+    }
+
+    #[test]
+    fn test_myers_distance() {
+        let a: Vec<_> = "kitten".chars().collect();
+        let b: Vec<_> = "sitting".chars().collect();
+        assert_eq!(myers_distance(&a, &b), 3);
+        assert_eq!(myers_distance(&b, &a), 3);
+        assert_eq!(myers_distance(&[], &b), 7);
+        assert_eq!(myers_distance(&a, &[]), 6);
+        let long: Vec<_> = (0..200).map(|i| i % 7).collect();
+        let shifted: Vec<_> = (1..201).map(|i| i % 7).collect();
+        assert_eq!(myers_distance(&long, &shifted), 2);
+    }
+
+    #[test]
+    fn test_random_fast_distance() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..300 {
+            let (n, m) = (rng.random_range(0..150), rng.random_range(0..150));
+            let sigma = rng.random_range(1..5);
+            let a: Vec<u8> = (0..n).map(|_| rng.random_range(0..sigma)).collect();
+            let mut b: Vec<u8> = (0..m).map(|_| rng.random_range(0..sigma)).collect();
+            if rng.random_bool(0.5) {
+                // similar sequences, so that the banded dp find the distance
+                b = a.clone();
+                for _ in 0..rng.random_range(0..10) {
+                    let i = rng.random_range(0..=b.len());
+                    if i < b.len() && rng.random_bool(0.5) {
+                        b.remove(i);
+                    } else {
+                        b.insert(i, rng.random_range(0..sigma));
+                    }
+                }
+            }
+            let distance = levenshtein_distance(&a, &b);
+            assert_eq!(myers_distance(&a, &b), distance);
+            let k = rng.random_range(0..20);
+            let expected = if distance <= k { Some(distance) } else { None };
+            assert_eq!(levenshtein_within(&a, &b, k), expected);
+        }
+    }
+
+    #[test]
+    fn test_random_script() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let a: Vec<u8> = (0..rng.random_range(0..10)).map(|_| rng.random_range(0..3)).collect();
+            let b: Vec<u8> = (0..rng.random_range(0..10)).map(|_| rng.random_range(0..3)).collect();
+            for transposition in [false, true] {
+                let (distance, script) = if transposition {
+                    osa_edit_script(&a, &b, &UnitCost)
+                } else {
+                    edit_script(&a, &b, &UnitCost)
+                };
+                assert_eq!(apply(&a, &b, &script), b);
+                let edits = script.iter().filter(|op| !matches!(op, EditOperation::Keep(_, _)));
+                assert_eq!(edits.count(), distance);
+            }
+            assert!(osa_distance(&a, &b, &UnitCost) <= levenshtein_distance(&a, &b));
+        }
+    }
 }
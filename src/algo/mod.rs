@@ -4,4 +4,5 @@ pub mod compress;
 pub mod distance;
 pub mod inversion;
 pub mod sort;
+pub mod subsequence;
 pub mod two_pointers;
@@ -80,9 +80,7 @@ pub fn heap_sort<T: PartialOrd>(data: &mut [T]) {
 /// **O(n log(n))**, stable sorted by merge sort
 pub fn merge_sort<T: PartialOrd + Clone>(data: &mut [T]) {
     fn merge_sort_recursive<T: PartialOrd + Clone>(data: &mut [T], from: usize, to: usize) {
-        if to - from < 2 {
-            return;
-        } else {
+        if to - from >= 2 {
             let mid = (from + to) / 2;
             merge_sort_recursive(data, from, mid);
             merge_sort_recursive(data, mid, to);
@@ -91,7 +89,7 @@ pub fn merge_sort<T: PartialOrd + Clone>(data: &mut [T]) {
     }
     fn merge<T: PartialOrd + Clone>(data: &mut [T], from: usize, mid: usize, to: usize) {
         let (mut left, mut right): (Vec<_>, Vec<_>) =
-            (data[from..mid].iter().cloned().collect(), data[mid..to].iter().cloned().collect());
+            (data[from..mid].to_vec(), data[mid..to].to_vec());
         for i in (from..to).rev() {
             data[i] = match (left.last(), right.last()) {
                 (Some(ll), Some(rl)) => {
@@ -114,9 +112,7 @@ pub fn merge_sort<T: PartialOrd + Clone>(data: &mut [T]) {
 /// **O(n log(n))**, sorted data by quick sort
 pub fn quick_sort<T: PartialOrd>(data: &mut [T]) {
     fn quick_sort_recursive<T: PartialOrd>(data: &mut [T], from: usize, to: usize) {
-        if to - from < 2 {
-            return;
-        } else {
+        if to - from >= 2 {
             let mut pivot_pos = (from + to) / 2;
             let (mut left, mut right) = (from, to - 1);
             while left < right {
@@ -188,22 +184,22 @@ mod tests {
 
     #[test]
     fn test_is_sorted_n_pow_2() {
-        use rand::Rng;
+        use rand::RngExt;
         for i in 0..10 {
             let mut v = vec![0.0; 100 * i];
-            rand::rng().fill(&mut v[..]);
+            v.fill_with(|| rand::rng().random());
             bubble_sort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
         }
         for i in 0..10 {
             let mut v = vec![0; 100 * i];
-            rand::rng().fill(&mut v[..]);
+            v.fill_with(|| rand::rng().random());
             selection_sort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
         }
         for i in 0..10 {
             let mut v = vec![0.0; 100 * i];
-            rand::rng().fill(&mut v[..]);
+            v.fill_with(|| rand::rng().random());
             insertion_sort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
         }
@@ -211,22 +207,22 @@ mod tests {
 
     #[test]
     fn test_is_sorted_n_log_n() {
-        use rand::Rng;
+        use rand::RngExt;
         for i in 0..30 {
             let mut v = vec![0.0; 100 * i];
-            rand::rng().fill(&mut v[..]);
+            v.fill_with(|| rand::rng().random());
             heap_sort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
         }
         for i in 0..30 {
             let mut v = vec![0.0; 100 * i];
-            rand::rng().fill(&mut v[..]);
+            v.fill_with(|| rand::rng().random());
             merge_sort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
         }
         for i in 0..30 {
             let mut v = vec![0.0; 100 * i];
-            rand::rng().fill(&mut v[..]);
+            v.fill_with(|| rand::rng().random());
             quick_sort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
         }
@@ -0,0 +1,298 @@
+use super::bisect::{bisect_left_by_key, bisect_right_by_key};
+use crate::collections::segtree::segtree::{Monoid, SegmentTree};
+use num::Num;
+use std::cmp::Ordering;
+
+/// **O(n log(n))**, lengths[i] is the length of the longest increasing subsequence that end at a[i],
+/// if strict is false then non-decreasing subsequence
+pub fn lis_lengths<T: Ord>(a: &[T], strict: bool) -> Vec<usize> {
+    lis_with_parents(a, strict).0
+}
+
+/// **O(n log(n))**, indices of one of the longest increasing subsequences,
+/// if strict is false then non-decreasing subsequence
+pub fn longest_increasing_subsequence<T: Ord>(a: &[T], strict: bool) -> Vec<usize> {
+    let (lengths, parents) = lis_with_parents(a, strict);
+    let mut last = (0..a.len()).max_by_key(|&i| (lengths[i], std::cmp::Reverse(i)));
+    let mut indices = Vec::new();
+    while let Some(i) = last {
+        indices.push(i);
+        last = parents[i];
+    }
+    indices.reverse();
+    indices
+}
+
+/// **O(n log(n))**, lengths of increasing subsequences that end at each index and their previous index
+fn lis_with_parents<T: Ord>(a: &[T], strict: bool) -> (Vec<usize>, Vec<Option<usize>>) {
+    // tails[l] is the minimum last element (and its index) of increasing subsequences of length l + 1
+    let mut tails: Vec<(&T, usize)> = Vec::new();
+    let (mut lengths, mut parents) = (vec![0; a.len()], vec![None; a.len()]);
+    for (i, x) in a.iter().enumerate() {
+        let l = if strict {
+            bisect_left_by_key(&tails, x, |tail| &tail.0)
+        } else {
+            bisect_right_by_key(&tails, x, |tail| &tail.0)
+        };
+        if l == tails.len() {
+            tails.push((x, i));
+        } else {
+            tails[l] = (x, i);
+        }
+        lengths[i] = l + 1;
+        parents[i] = l.checked_sub(1).map(|p| tails[p].1);
+    }
+    (lengths, parents)
+}
+
+/// **O(n log(n))**, length of the longest increasing subsequence and the number of such subsequences
+/// as sets of indices (C is modint when the number is large), if strict is false then non-decreasing
+pub fn count_longest_increasing_subsequences<T, C>(a: &[T], strict: bool) -> (usize, C)
+where
+    T: Ord,
+    C: Num + Copy,
+{
+    if a.is_empty() {
+        return (0, C::one());
+    }
+    let mut values: Vec<_> = a.iter().collect();
+    values.sort();
+    values.dedup();
+    // tree[v] is (length, count) of the longest increasing subsequences that end at value v so far
+    let mut tree = SegmentTree::<LongestCount<C>>::new(&vec![(0, C::zero()); values.len()]);
+    for x in a {
+        let v = values.binary_search(&x).expect("values contain all elements");
+        let (length, count) = if strict { tree.query(..v) } else { tree.query(..=v) };
+        let end_at_x = LongestCount(length + 1, if length == 0 { C::one() } else { count });
+        tree.update_with(v, |&old| Monoid::into(LongestCount::operation(&old.into(), &end_at_x)));
+    }
+    tree.query(..)
+}
+
+/// (length, count) of the longest subsequences, longer one is taken and counts of ties are summed
+#[derive(Clone, Copy)]
+struct LongestCount<C>(usize, C);
+impl<C> From<(usize, C)> for LongestCount<C> {
+    fn from((length, count): (usize, C)) -> Self {
+        LongestCount(length, count)
+    }
+}
+impl<C: Num + Copy> Monoid<(usize, C)> for LongestCount<C> {
+    fn identity() -> Self {
+        LongestCount(0, C::zero())
+    }
+    fn operation(a: &Self, b: &Self) -> Self {
+        match a.0.cmp(&b.0) {
+            Ordering::Less => *b,
+            Ordering::Greater => *a,
+            Ordering::Equal => LongestCount(a.0, a.1 + b.1),
+        }
+    }
+    fn into(self) -> (usize, C) {
+        (self.0, self.1)
+    }
+}
+
+/// **O(nm)**, index pairs (i, j) of one of the longest common subsequences, a[i] == b[j]
+pub fn longest_common_subsequence<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len(), b.len());
+    // dp[i][j] is the length of lcs of a[..i] and b[..j]
+    let mut dp = vec![vec![0; m + 1]; n + 1];
+    for i in 0..n {
+        for j in 0..m {
+            dp[i + 1][j + 1] =
+                if a[i] == b[j] { dp[i][j] + 1 } else { dp[i][j + 1].max(dp[i + 1][j]) };
+        }
+    }
+    let (mut i, mut j, mut pairs) = (n, m, Vec::new());
+    while i > 0 && j > 0 {
+        if a[i - 1] == b[j - 1] {
+            pairs.push((i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if dp[i][j] == dp[i - 1][j] {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    pairs.reverse();
+    pairs
+}
+
+/// **O(nm)** time and **O(n + m)** memory, same as longest_common_subsequence by Hirschberg's algorithm
+pub fn hirschberg_lcs<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    hirschberg_recursive(a, b, 0, 0, &mut pairs);
+    pairs
+}
+
+/// **O(nm)**, divide a into halves and b at the point that lcs is split optimally
+fn hirschberg_recursive<T: PartialEq>(
+    a: &[T],
+    b: &[T],
+    a_offset: usize,
+    b_offset: usize,
+    pairs: &mut Vec<(usize, usize)>,
+) {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return;
+    }
+    if n == 1 {
+        if let Some(j) = b.iter().position(|y| y == &a[0]) {
+            pairs.push((a_offset, b_offset + j));
+        }
+        return;
+    }
+    let mid = n / 2;
+    let forward = lcs_last_row(a[..mid].iter(), b.iter(), m);
+    let backward = lcs_last_row(a[mid..].iter().rev(), b.iter().rev(), m);
+    let split = (0..=m).max_by_key(|&j| forward[j] + backward[m - j]).expect("0..=m is not empty");
+    hirschberg_recursive(&a[..mid], &b[..split], a_offset, b_offset, pairs);
+    hirschberg_recursive(&a[mid..], &b[split..], a_offset + mid, b_offset + split, pairs);
+}
+
+/// **O(nm)** time and **O(m)** memory, row[j] is the length of lcs of a and b[..j]
+fn lcs_last_row<'a, T, I, J>(a: I, b: J, m: usize) -> Vec<usize>
+where
+    T: 'a + PartialEq,
+    I: Iterator<Item = &'a T>,
+    J: Iterator<Item = &'a T> + Clone,
+{
+    let mut row = vec![0; m + 1];
+    for x in a {
+        let mut diagonal = 0; // previous row[j]
+        for (j, y) in b.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if x == y { diagonal + 1 } else { above.max(row[j]) };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algo::compress::coordinate_compress;
+    use rand::RngExt;
+
+    #[test]
+    fn test_lis() {
+        let a = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        assert_eq!(lis_lengths(&a, true), [1, 1, 2, 1, 3, 4, 2, 4, 3, 3, 4]);
+        assert_eq!(lis_lengths(&a, false), [1, 1, 2, 2, 3, 4, 3, 4, 4, 4, 5]);
+        let lis = longest_increasing_subsequence(&a, true);
+        assert_eq!(lis.len(), 4);
+        assert!(lis.windows(2).all(|w| w[0] < w[1] && a[w[0]] < a[w[1]]));
+        let lis = longest_increasing_subsequence(&a, false);
+        assert_eq!(lis.iter().map(|&i| a[i]).collect::<Vec<_>>(), [1, 1, 2, 3, 5]);
+        assert_eq!(longest_increasing_subsequence(&[2, 2, 2], true), [0]);
+        assert_eq!(longest_increasing_subsequence(&[2, 2, 2], false), [0, 1, 2]);
+        assert_eq!(longest_increasing_subsequence::<u8>(&[], true), []);
+    }
+
+    #[test]
+    fn test_lis_compress() {
+        let a = ["rust", "python", "ruby", "perl", "scala", "go"];
+        let compressed = coordinate_compress(&a);
+        for &strict in &[true, false] {
+            assert_eq!(lis_lengths(&a, strict), lis_lengths(&compressed, strict));
+            let lis = longest_increasing_subsequence(&a, strict);
+            assert_eq!(lis, longest_increasing_subsequence(&compressed, strict));
+        }
+    }
+
+    #[test]
+    fn test_lis_naive() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let a: Vec<u8> =
+                (0..rng.random_range(0..30)).map(|_| rng.random_range(0..10)).collect();
+            for &strict in &[true, false] {
+                let increasing = |x: &u8, y: &u8| if strict { x < y } else { x <= y };
+                let mut naive = vec![1; a.len()];
+                for i in 0..a.len() {
+                    for j in 0..i {
+                        if increasing(&a[j], &a[i]) {
+                            naive[i] = naive[i].max(naive[j] + 1);
+                        }
+                    }
+                }
+                assert_eq!(lis_lengths(&a, strict), naive);
+                let lis = longest_increasing_subsequence(&a, strict);
+                assert_eq!(lis.len(), naive.iter().cloned().max().unwrap_or(0));
+                assert!(lis.windows(2).all(|w| w[0] < w[1] && increasing(&a[w[0]], &a[w[1]])));
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_lis() {
+        let a = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        // 3 4 5 9, 3 4 5 6, 1 4 5 9, 1 4 5 6 and 1 2 3 5, where 1 is a[1] and 1 2 3 5 can start at a[3]
+        assert_eq!(count_longest_increasing_subsequences::<_, u64>(&a, true), (4, 6));
+        assert_eq!(count_longest_increasing_subsequences::<_, u64>(&[2, 2, 2], true), (1, 3));
+        assert_eq!(count_longest_increasing_subsequences::<_, u64>(&[2, 2, 2], false), (3, 1));
+        assert_eq!(count_longest_increasing_subsequences::<u8, u64>(&[], true), (0, 1));
+        // 2^50 longest subsequences of 1 0 3 2 5 4 ...
+        let a: Vec<_> = (0..100).map(|i| i ^ 1).collect();
+        assert_eq!(count_longest_increasing_subsequences::<_, u64>(&a, true), (50, 1 << 50));
+    }
+
+    #[test]
+    fn test_count_lis_naive() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let a: Vec<u8> =
+                (0..rng.random_range(1..30)).map(|_| rng.random_range(0..10)).collect();
+            for &strict in &[true, false] {
+                let increasing = |x: &u8, y: &u8| if strict { x < y } else { x <= y };
+                // dp[i] is (length, count) of the longest subsequences that end at a[i]
+                let mut dp = vec![(1, 1u64); a.len()];
+                for i in 0..a.len() {
+                    for j in 0..i {
+                        if increasing(&a[j], &a[i]) {
+                            if dp[j].0 + 1 > dp[i].0 {
+                                dp[i] = (dp[j].0 + 1, dp[j].1);
+                            } else if dp[j].0 + 1 == dp[i].0 {
+                                dp[i].1 += dp[j].1;
+                            }
+                        }
+                    }
+                }
+                let length = dp.iter().map(|d| d.0).max().unwrap();
+                let count: u64 = dp.iter().filter(|d| d.0 == length).map(|d| d.1).sum();
+                assert_eq!(count_longest_increasing_subsequences(&a, strict), (length, count));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lcs() {
+        let a: Vec<_> = "ABCBDAB".chars().collect();
+        let b: Vec<_> = "BDCABA".chars().collect();
+        for pairs in [longest_common_subsequence(&a, &b), hirschberg_lcs(&a, &b)] {
+            assert_eq!(pairs.len(), 4);
+            assert!(pairs.iter().all(|&(i, j)| a[i] == b[j]));
+            assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+        }
+        assert_eq!(longest_common_subsequence(&a, &[]), []);
+        assert_eq!(hirschberg_lcs(&[], &b), []);
+        assert_eq!(hirschberg_lcs(&a, &a), (0..7).map(|i| (i, i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_hirschberg_random() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let a: Vec<u8> = (0..rng.random_range(0..40)).map(|_| rng.random_range(0..4)).collect();
+            let b: Vec<u8> = (0..rng.random_range(0..40)).map(|_| rng.random_range(0..4)).collect();
+            let (expected, pairs) = (longest_common_subsequence(&a, &b), hirschberg_lcs(&a, &b));
+            assert_eq!(pairs.len(), expected.len());
+            assert!(pairs.iter().all(|&(i, j)| a[i] == b[j]));
+            assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+        }
+    }
+}
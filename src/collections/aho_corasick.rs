@@ -0,0 +1,270 @@
+use crate::collections::trie_map::TrieMap;
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+type StateId = usize;
+
+#[derive(Debug)]
+struct State<E> {
+    children: HashMap<E, StateId>,
+    fail: StateId,         // longest proper suffix that is a state
+    dict: Option<StateId>, // longest proper suffix that is end of some pattern
+    outputs: Vec<usize>,   // patterns that end at this state
+    match_count: usize,    // number of patterns that end at this state or its suffix
+}
+
+/// automaton that find all occurrences of multiple patterns, state 0 is root (empty string)
+#[derive(Debug)]
+pub struct AhoCorasick<E> {
+    states: Vec<State<E>>,
+    pattern_lens: Vec<usize>,
+}
+impl<E> AhoCorasick<E>
+where
+    E: Hash + Eq + Clone,
+{
+    /// **O(Σ|pattern|)**, build automaton from patterns, pattern_id is index of the iterator
+    pub fn new<I, K>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: IntoIterator<Item = E>,
+    {
+        let mut trie = TrieMap::new();
+        let mut pattern_lens = Vec::new();
+        for pattern in patterns {
+            let pattern: Vec<_> = pattern.into_iter().collect();
+            trie.entry(pattern.iter().cloned()).or_insert_with(Vec::new).push(pattern_lens.len());
+            pattern_lens.push(pattern.len());
+        }
+        // number the trie nodes in bfs order, so that parents and failure links come first
+        let mut states = Vec::new();
+        let mut queue: VecDeque<_> = vec![trie.root].into_iter().collect();
+        while let Some(node) = queue.pop_front() {
+            let mut state = Self::new_state();
+            state.outputs = node.value.unwrap_or_default();
+            for (e, child) in node.children {
+                state.children.insert(e, states.len() + queue.len() + 1);
+                queue.push_back(child);
+            }
+            states.push(state);
+        }
+        let mut ac = AhoCorasick { states, pattern_lens };
+        ac.build();
+        ac
+    }
+
+    /// **O(1)**, make state that has no child
+    fn new_state() -> State<E> {
+        State { children: HashMap::new(), fail: 0, dict: None, outputs: Vec::new(), match_count: 0 }
+    }
+
+    /// **O(Σ|pattern|)**, calculate failure links and dictionary suffix links in bfs order
+    fn build(&mut self) {
+        self.states[0].match_count = self.states[0].outputs.len();
+        for s in 0..self.states.len() {
+            let children: Vec<_> =
+                self.states[s].children.iter().map(|(e, &c)| (e.clone(), c)).collect();
+            for (e, child) in children {
+                let fail = if s == 0 { 0 } else { self.next_state(self.states[s].fail, &e) };
+                let dict = if self.states[fail].outputs.is_empty() {
+                    self.states[fail].dict
+                } else {
+                    Some(fail)
+                };
+                let match_count = self.states[child].outputs.len() + self.states[fail].match_count;
+                let state = &mut self.states[child];
+                state.fail = fail;
+                state.dict = dict;
+                state.match_count = match_count;
+            }
+        }
+    }
+
+    /// **O(1)**, return the number of states, states are numbered from 0 to state_count - 1
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// **O(1)**, return the number of patterns
+    pub fn pattern_count(&self) -> usize {
+        self.pattern_lens.len()
+    }
+
+    /// **O(1)**, return true if the automaton has no pattern, else return false
+    pub fn is_empty(&self) -> bool {
+        self.pattern_lens.is_empty()
+    }
+
+    /// **O(1)**, length of the pattern
+    pub fn pattern_len(&self, pattern_id: usize) -> usize {
+        self.pattern_lens[pattern_id]
+    }
+
+    /// **O(1)** amortized while scanning text, transition from state by e, following failure links
+    pub fn next_state(&self, state: StateId, e: &E) -> StateId {
+        let mut current = state;
+        loop {
+            if let Some(&next) = self.states[current].children.get(e) {
+                return next;
+            } else if current == 0 {
+                return 0;
+            }
+            current = self.states[current].fail;
+        }
+    }
+
+    /// **O(1)**, return true if some pattern end at the state (including its suffixes)
+    pub fn is_match(&self, state: StateId) -> bool {
+        self.states[state].match_count > 0
+    }
+
+    /// **O(1)**, the number of patterns that end at the state (including its suffixes)
+    pub fn match_count(&self, state: StateId) -> usize {
+        self.states[state].match_count
+    }
+
+    /// **O(#matched)**, patterns that end at the state (including its suffixes)
+    pub fn matched_patterns(&self, state: StateId) -> Vec<usize> {
+        let mut patterns = self.states[state].outputs.clone();
+        let mut dict = self.states[state].dict;
+        while let Some(d) = dict {
+            patterns.extend(self.states[d].outputs.iter().cloned());
+            dict = self.states[d].dict;
+        }
+        patterns
+    }
+
+    /// **O(n + #matched)**, find all (pattern_id, end) that text[end - pattern_len(pattern_id)..end] is pattern
+    pub fn find_all(&self, text: &[E]) -> Vec<(usize, usize)> {
+        let mut matches: Vec<_> = self.states[0].outputs.iter().map(|&p| (p, 0)).collect();
+        let mut state = 0;
+        for (i, e) in text.iter().enumerate() {
+            state = self.next_state(state, e);
+            matches.extend(self.matched_patterns(state).into_iter().map(|p| (p, i + 1)));
+        }
+        matches
+    }
+
+    /// **O(n)**, count all occurrences of all patterns in text
+    pub fn count_matches(&self, text: &[E]) -> usize {
+        let mut state = 0;
+        let mut count = self.states[0].match_count;
+        for e in text {
+            state = self.next_state(state, e);
+            count += self.states[state].match_count;
+        }
+        count
+    }
+
+    /// **O(#states * |alphabet|)**, dense transition table, table[state][i] is next state by alphabet[i]
+    pub fn transition_table(&self, alphabet: &[E]) -> Vec<Vec<StateId>> {
+        let mut table = vec![vec![0; alphabet.len()]; self.state_count()];
+        // states are numbered in bfs order, so table of failure link is already filled
+        for s in 0..self.state_count() {
+            for (i, e) in alphabet.iter().enumerate() {
+                table[s][i] = match self.states[s].children.get(e) {
+                    Some(&child) => child,
+                    None if s == 0 => 0,
+                    None => table[self.states[s].fail][i],
+                };
+            }
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_all() {
+        let ac = AhoCorasick::new(vec!["he".chars(), "she".chars(), "his".chars(), "hers".chars()]);
+        let text: Vec<_> = "ushers".chars().collect();
+        let mut matches = ac.find_all(&text);
+        matches.sort();
+        assert_eq!(matches, vec![(0, 4), (1, 4), (3, 6)]);
+        assert_eq!(ac.count_matches(&text), 3);
+        assert_eq!(ac.pattern_len(3), 4);
+        assert_eq!(ac.pattern_count(), 4);
+        assert_eq!(ac.state_count(), 10);
+        assert!(!ac.is_empty());
+        assert!(AhoCorasick::<char>::new(Vec::<Vec<char>>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_overlap_and_duplicate() {
+        let ac = AhoCorasick::new(vec![vec![1, 1], vec![1], vec![1, 1]]);
+        let mut matches = ac.find_all(&[1, 1, 1]);
+        matches.sort();
+        assert_eq!(matches, vec![(0, 2), (0, 3), (1, 1), (1, 2), (1, 3), (2, 2), (2, 3)]);
+        assert_eq!(ac.count_matches(&[1, 1, 1]), 7);
+        assert_eq!(ac.count_matches(&[2, 1, 2]), 1);
+    }
+
+    #[test]
+    fn test_transition_table() {
+        let ac = AhoCorasick::new(vec!["ab".bytes(), "bab".bytes()]);
+        let table = ac.transition_table(b"ab");
+        let text = b"abbabab";
+        let mut state = 0;
+        for e in text {
+            let next = table[state][(e - b'a') as usize];
+            assert_eq!(next, ac.next_state(state, e));
+            state = next;
+        }
+        assert!(ac.is_match(state));
+        assert_eq!(ac.matched_patterns(state).len(), 2);
+    }
+
+    #[test]
+    fn test_random_count() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let patterns: Vec<Vec<u8>> = (0..5)
+                .map(|_| (0..rng.random_range(1..4)).map(|_| rng.random_range(0..3)).collect())
+                .collect();
+            let text: Vec<u8> = (0..50).map(|_| rng.random_range(0..3)).collect();
+            let ac = AhoCorasick::new(patterns.clone());
+            let naive: usize =
+                patterns.iter().map(|p| text.windows(p.len()).filter(|w| w == p).count()).sum();
+            assert_eq!(ac.count_matches(&text), naive);
+            assert_eq!(ac.find_all(&text).len(), naive);
+        }
+    }
+
+    #[test]
+    fn test_dp_avoiding_patterns() {
+        use crate::integer::modint::ModInt;
+        // count strings of length n over {a, b, c} that contain neither "ab" nor "ca"
+        let alphabet: Vec<_> = "abc".chars().collect();
+        let ac = AhoCorasick::new(vec!["ab".chars(), "ca".chars()]);
+        let table = ac.transition_table(&alphabet);
+        let n = 10;
+        let mut dp = vec![ModInt::new(0u64, 1_000_000_007); ac.state_count()];
+        dp[0] = ModInt::new(1, 1_000_000_007);
+        for _ in 0..n {
+            let mut next = vec![ModInt::new(0, 1_000_000_007); ac.state_count()];
+            for s in (0..ac.state_count()).filter(|&s| !ac.is_match(s)) {
+                for c in 0..alphabet.len() {
+                    next[table[s][c]] += dp[s];
+                }
+            }
+            dp = next;
+        }
+        let count = (0..ac.state_count())
+            .filter(|&s| !ac.is_match(s))
+            .fold(ModInt::new(0, 1_000_000_007), |sum, s| sum + dp[s]);
+        // brute force over all 3^n strings
+        let naive = (0..3usize.pow(n))
+            .filter(|&x| {
+                let s: Vec<_> = (0..n).map(|i| alphabet[x / 3usize.pow(i) % 3]).collect();
+                ac.count_matches(&s) == 0
+            })
+            .count();
+        assert_eq!(count, ModInt::new(naive as u64, 1_000_000_007));
+    }
+}
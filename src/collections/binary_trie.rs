@@ -0,0 +1,242 @@
+/// multiset of fixed-width unsigned integers, each node has children by bit and count of elements
+#[derive(Debug, Clone)]
+pub struct BinaryTrie {
+    bits: usize,
+    children: Vec<[usize; 2]>, // 0 mean no child, because root (index 0) is never a child
+    counts: Vec<usize>,
+}
+impl BinaryTrie {
+    /// **O(1)**, make empty trie of integers in [0, 2^bits), bits should be at most 64
+    pub fn new(bits: usize) -> Self {
+        assert!(bits <= 64, "bits {} is larger than 64", bits);
+        BinaryTrie { bits, children: vec![[0; 2]], counts: vec![0] }
+    }
+
+    /// **O(1)**, make empty trie of u32
+    pub fn new_u32() -> Self {
+        Self::new(32)
+    }
+
+    /// **O(1)**, make empty trie of u64
+    pub fn new_u64() -> Self {
+        Self::new(64)
+    }
+
+    /// **O(1)**, return the number of elements
+    pub fn len(&self) -> usize {
+        self.counts[0]
+    }
+
+    /// **O(1)**, return true if the trie has no element, else return false
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// **O(1)**, i-th bit from the top of x
+    fn bit(&self, x: u64, depth: usize) -> usize {
+        (x >> (self.bits - 1 - depth)) as usize & 1
+    }
+
+    /// **O(bits)**, find the node of x
+    fn find(&self, x: u64) -> Option<usize> {
+        let mut node = 0;
+        for depth in 0..self.bits {
+            node = self.children[node][self.bit(x, depth)];
+            if node == 0 || self.counts[node] == 0 {
+                return None;
+            }
+        }
+        Some(node)
+    }
+
+    /// **O(bits)**, insert x
+    pub fn insert(&mut self, x: u64) {
+        assert!(self.bits == 64 || x >> self.bits == 0, "{} is out of {} bits", x, self.bits);
+        let mut node = 0;
+        self.counts[node] += 1;
+        for depth in 0..self.bits {
+            let b = self.bit(x, depth);
+            if self.children[node][b] == 0 {
+                self.children[node][b] = self.children.len();
+                self.children.push([0; 2]);
+                self.counts.push(0);
+            }
+            node = self.children[node][b];
+            self.counts[node] += 1;
+        }
+    }
+
+    /// **O(bits)**, erase one x, if there is no x then return false
+    pub fn erase(&mut self, x: u64) -> bool {
+        if self.find(x).is_none() {
+            return false;
+        }
+        let mut node = 0;
+        self.counts[node] -= 1;
+        for depth in 0..self.bits {
+            node = self.children[node][self.bit(x, depth)];
+            self.counts[node] -= 1;
+        }
+        true
+    }
+
+    /// **O(bits)**, count the number of x
+    pub fn count(&self, x: u64) -> usize {
+        self.find(x).map_or(0, |node| self.counts[node])
+    }
+
+    /// **O(bits)**, calculate min(x ^ y) for y in this trie
+    pub fn min_xor(&self, x: u64) -> Option<u64> {
+        self.xor_extremum(x, false)
+    }
+
+    /// **O(bits)**, calculate max(x ^ y) for y in this trie
+    pub fn max_xor(&self, x: u64) -> Option<u64> {
+        self.xor_extremum(x, true)
+    }
+
+    /// **O(bits)**, follow same bit as x greedily (or opposite bit, if maximize)
+    fn xor_extremum(&self, x: u64, maximize: bool) -> Option<u64> {
+        if self.is_empty() {
+            return None;
+        }
+        let (mut node, mut result) = (0, 0);
+        for depth in 0..self.bits {
+            let preferred = self.bit(x, depth) ^ maximize as usize;
+            let next = self.children[node][preferred];
+            let b = if next != 0 && self.counts[next] > 0 { preferred } else { preferred ^ 1 };
+            result = result << 1 | (b ^ self.bit(x, depth)) as u64;
+            node = self.children[node][b];
+        }
+        Some(result)
+    }
+
+    /// **O(bits)**, calculate k-th (0-indexed) smallest of y ^ xor_mask for y in this trie
+    pub fn kth_smallest(&self, k: usize, xor_mask: u64) -> Option<u64> {
+        if k >= self.len() {
+            return None;
+        }
+        let (mut node, mut k, mut result) = (0, k, 0);
+        for depth in 0..self.bits {
+            let b = self.bit(xor_mask, depth);
+            let smaller = self.children[node][b];
+            let smaller_count = if smaller == 0 { 0 } else { self.counts[smaller] };
+            if k < smaller_count {
+                result <<= 1;
+                node = smaller;
+            } else {
+                k -= smaller_count;
+                result = result << 1 | 1;
+                node = self.children[node][b ^ 1];
+            }
+        }
+        Some(result)
+    }
+
+    /// **O(bits)**, count the number of y such that y < x
+    pub fn count_less(&self, x: u64) -> usize {
+        self.count_less_xor(x, 0)
+    }
+
+    /// **O(bits)**, count the number of y such that (y ^ xor_mask) < x
+    pub fn count_less_xor(&self, x: u64, xor_mask: u64) -> usize {
+        let (mut node, mut count) = (0, 0);
+        for depth in 0..self.bits {
+            let (xb, mb) = (self.bit(x, depth), self.bit(xor_mask, depth));
+            if xb == 1 {
+                // all y whose (y ^ xor_mask) has 0 at this bit are smaller than x
+                let smaller = self.children[node][mb];
+                count += if smaller == 0 { 0 } else { self.counts[smaller] };
+            }
+            node = self.children[node][xb ^ mb];
+            if node == 0 {
+                break;
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_erase_count() {
+        let mut trie = BinaryTrie::new(4);
+        assert!(trie.is_empty());
+        trie.insert(3);
+        trie.insert(3);
+        trie.insert(12);
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.count(3), 2);
+        assert_eq!(trie.count(12), 1);
+        assert_eq!(trie.count(2), 0);
+        assert!(trie.erase(3));
+        assert_eq!(trie.count(3), 1);
+        assert!(!trie.erase(5));
+        assert!(trie.erase(3));
+        assert!(!trie.erase(3));
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn test_min_max_xor() {
+        let mut trie = BinaryTrie::new_u32();
+        assert_eq!(trie.min_xor(0), None);
+        for &x in &[3, 10, 5, 25, 2, 8] {
+            trie.insert(x);
+        }
+        // maximum xor pair is 5 ^ 25 = 28
+        assert_eq!([3, 10, 5, 25, 2, 8].iter().map(|&x| trie.max_xor(x).unwrap()).max(), Some(28));
+        assert_eq!(trie.min_xor(7), Some(2));
+        assert_eq!(trie.min_xor(8), Some(0));
+        assert_eq!(trie.max_xor(0), Some(25));
+        trie.erase(25);
+        assert_eq!(trie.max_xor(0), Some(10));
+    }
+
+    #[test]
+    fn test_kth_count_less() {
+        let mut trie = BinaryTrie::new_u64();
+        for &x in &[7, 1, 4, 4, 9, u64::MAX] {
+            trie.insert(x);
+        }
+        let sorted = [1, 4, 4, 7, 9, u64::MAX];
+        for (k, &x) in sorted.iter().enumerate() {
+            assert_eq!(trie.kth_smallest(k, 0), Some(x));
+        }
+        assert_eq!(trie.kth_smallest(6, 0), None);
+        assert_eq!(trie.kth_smallest(0, 5), Some(1));
+        assert_eq!(trie.kth_smallest(1, 5), Some(1));
+        assert_eq!(trie.count_less(0), 0);
+        assert_eq!(trie.count_less(4), 1);
+        assert_eq!(trie.count_less(5), 3);
+        assert_eq!(trie.count_less(u64::MAX), 5);
+    }
+
+    #[test]
+    fn test_random_xor() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        let mut trie = BinaryTrie::new(10);
+        let mut v = Vec::new();
+        for _ in 0..300 {
+            let x = rng.random_range(0..1024u64);
+            trie.insert(x);
+            v.push(x);
+            if rng.random_bool(0.3) {
+                let y = v.swap_remove(rng.random_range(0..v.len()));
+                assert!(trie.erase(y));
+            }
+            let (q, mask) = (rng.random_range(0..1024u64), rng.random_range(0..1024u64));
+            assert_eq!(trie.min_xor(q), v.iter().map(|&y| y ^ q).min());
+            assert_eq!(trie.max_xor(q), v.iter().map(|&y| y ^ q).max());
+            assert_eq!(trie.count_less_xor(q, mask), v.iter().filter(|&&y| y ^ mask < q).count());
+            let mut xored: Vec<_> = v.iter().map(|&y| y ^ mask).collect();
+            xored.sort();
+            let k = rng.random_range(0..v.len() + 1);
+            assert_eq!(trie.kth_smallest(k, mask), xored.get(k).cloned());
+        }
+    }
+}
@@ -0,0 +1,441 @@
+use std::marker::PhantomData;
+
+use crate::collections::{segtree::segtree::Monoid, treap::implicit_treap::Action};
+
+type NodeId = usize;
+
+#[derive(Clone)]
+struct Node<M, A> {
+    value: M,
+    sum: M,
+    rev_sum: M, // aggregation of reversed splay subtree, non-commutative monoid need it for evert
+    size: usize,
+    rev: bool,       // children should be swapped (but this node's sum is already reversed)
+    lazy: Option<A>, // map that should be applied to children (None mean identity)
+    parent: Option<NodeId>, // parent in splay tree, or path-parent if this node is splay root
+    left: Option<NodeId>,
+    right: Option<NodeId>,
+}
+
+/// dynamic forest, each preferred path is managed by splay tree
+pub struct LinkCutTree<T, M, A = ()> {
+    nodes: Vec<Node<M, A>>,
+    element: PhantomData<T>,
+}
+impl<T, M, A> LinkCutTree<T, M, A>
+where
+    M: Monoid<T> + Clone,
+    A: Action<M> + Clone,
+{
+    /// **O(n)**, make forest that has no edge, node i has value data[i]
+    pub fn new(data: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let nodes = data
+            .iter()
+            .map(|d| {
+                let value = M::from(d.clone());
+                let (sum, rev_sum) = (value.clone(), value.clone());
+                Node {
+                    value,
+                    sum,
+                    rev_sum,
+                    size: 1,
+                    rev: false,
+                    lazy: None,
+                    parent: None,
+                    left: None,
+                    right: None,
+                }
+            })
+            .collect();
+        LinkCutTree { nodes, element: PhantomData }
+    }
+
+    /// **O(1)**, return the number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// **O(1)**, return true if the forest has no node, else return false
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// **O(log(n))** amortized, make u the root of its tree,
+    /// evert and link are the only operations that change the root
+    pub fn evert(&mut self, u: NodeId) {
+        self.access(u);
+        self.reverse_node(u);
+        self.propagation(u);
+    }
+
+    /// **O(log(n))** amortized, return root of the tree that u belongs to
+    pub fn root(&mut self, u: NodeId) -> NodeId {
+        self.access(u);
+        let mut current = u;
+        self.propagation(current);
+        while let Some(left) = self.nodes[current].left {
+            current = left;
+            self.propagation(current);
+        }
+        self.splay(current);
+        current
+    }
+
+    /// **O(log(n))** amortized, return true if u and v belong to same tree, else return false
+    pub fn connected(&mut self, u: NodeId, v: NodeId) -> bool {
+        u == v || self.root(u) == self.root(v)
+    }
+
+    /// **O(log(n))** amortized, add edge (u, v), if they are already connected then return false,
+    /// tree of u is hung under v, so root of the merged tree is the root of v
+    pub fn link(&mut self, u: NodeId, v: NodeId) -> bool {
+        if self.connected(u, v) {
+            return false;
+        }
+        self.evert(u);
+        self.nodes[u].parent = Some(v);
+        true
+    }
+
+    /// **O(log(n))** amortized, remove edge (u, v), if there is no such edge then return false,
+    /// the component that does not contain the old root is rooted at u or v
+    pub fn cut(&mut self, u: NodeId, v: NodeId) -> bool {
+        if u == v || !self.connected(u, v) {
+            return false;
+        }
+        let root = self.root(u);
+        self.evert(u);
+        self.access(v);
+        // path u-v is the splay tree of v, so the edge exist iff u is just before v
+        let exists = self.nodes[v].left == Some(u) && self.nodes[u].right.is_none();
+        if exists {
+            self.nodes[v].left = None;
+            self.nodes[u].parent = None;
+            self.update(v);
+        }
+        self.evert(root);
+        exists
+    }
+
+    /// **O(log(n))** amortized, lowest common ancestor of u and v under current root
+    pub fn lca(&mut self, u: NodeId, v: NodeId) -> Option<NodeId> {
+        if !self.connected(u, v) {
+            return None;
+        }
+        self.access(u);
+        Some(self.access(v))
+    }
+
+    /// **O(log(n))** amortized, get value of node u
+    pub fn get(&mut self, u: NodeId) -> T {
+        self.access(u);
+        self.nodes[u].value.clone().into()
+    }
+
+    /// **O(log(n))** amortized, set value of node u to x
+    pub fn set(&mut self, u: NodeId, x: T) {
+        self.access(u);
+        self.nodes[u].value = M::from(x);
+        self.update(u);
+    }
+
+    /// **O(log(n))** amortized, calculate aggregation of the path from u to v, the root is kept
+    pub fn query(&mut self, u: NodeId, v: NodeId) -> Option<T> {
+        if !self.connected(u, v) {
+            return None;
+        }
+        let root = self.root(u);
+        self.evert(u);
+        self.access(v);
+        let sum = self.nodes[v].sum.clone().into();
+        self.evert(root);
+        Some(sum)
+    }
+
+    /// **O(log(n))** amortized, apply map f to all nodes on the path from u to v, the root is kept
+    pub fn apply(&mut self, u: NodeId, v: NodeId, f: A) -> bool {
+        if !self.connected(u, v) {
+            return false;
+        }
+        let root = self.root(u);
+        self.evert(u);
+        self.access(v);
+        self.apply_node(v, &f);
+        self.evert(root);
+        true
+    }
+
+    /// **O(log(n))** amortized, make the path from root to u preferred, and return the last jumped node
+    fn access(&mut self, u: NodeId) -> NodeId {
+        let (mut last, mut current) = (None, Some(u));
+        let mut jumped = u;
+        while let Some(c) = current {
+            self.splay(c);
+            self.nodes[c].right = last;
+            self.update(c);
+            jumped = c;
+            last = Some(c);
+            current = self.nodes[c].parent;
+        }
+        self.splay(u);
+        jumped
+    }
+
+    /// **O(1)**, return true if u is root of the splay tree, else return false
+    fn is_splay_root(&self, u: NodeId) -> bool {
+        match self.nodes[u].parent {
+            Some(p) => self.nodes[p].left != Some(u) && self.nodes[p].right != Some(u),
+            None => true,
+        }
+    }
+
+    /// **O(log(n))** amortized, make u the root of its splay tree
+    fn splay(&mut self, u: NodeId) {
+        // propagate from splay root before rotation
+        let mut path = vec![u];
+        while !self.is_splay_root(path[path.len() - 1]) {
+            path.push(self.nodes[path[path.len() - 1]].parent.expect("not a splay root"));
+        }
+        for &p in path.iter().rev() {
+            self.propagation(p);
+        }
+        while !self.is_splay_root(u) {
+            let p = self.nodes[u].parent.expect("not a splay root");
+            if !self.is_splay_root(p) {
+                let g = self.nodes[p].parent.expect("not a splay root");
+                let zig_zig = (self.nodes[g].left == Some(p)) == (self.nodes[p].left == Some(u));
+                self.rotate(if zig_zig { p } else { u });
+            }
+            self.rotate(u);
+        }
+    }
+
+    /// **O(1)**, rotate u and its parent
+    fn rotate(&mut self, u: NodeId) {
+        let p = self.nodes[u].parent.expect("rotated node has parent");
+        let g = self.nodes[p].parent;
+        let p_is_root = self.is_splay_root(p);
+        if self.nodes[p].left == Some(u) {
+            let b = self.nodes[u].right;
+            self.nodes[p].left = b;
+            self.nodes[u].right = Some(p);
+            if let Some(b) = b {
+                self.nodes[b].parent = Some(p);
+            }
+        } else {
+            let b = self.nodes[u].left;
+            self.nodes[p].right = b;
+            self.nodes[u].left = Some(p);
+            if let Some(b) = b {
+                self.nodes[b].parent = Some(p);
+            }
+        }
+        self.nodes[p].parent = Some(u);
+        self.nodes[u].parent = g;
+        if let (Some(g), false) = (g, p_is_root) {
+            if self.nodes[g].left == Some(p) {
+                self.nodes[g].left = Some(u);
+            } else {
+                self.nodes[g].right = Some(u);
+            }
+        }
+        self.update(p);
+        self.update(u);
+    }
+
+    /// **O(1)**, reverse the splay subtree lazily
+    fn reverse_node(&mut self, u: NodeId) {
+        let node = &mut self.nodes[u];
+        std::mem::swap(&mut node.sum, &mut node.rev_sum);
+        node.rev ^= true;
+    }
+
+    /// **O(1)**, apply map to the splay subtree lazily
+    fn apply_node(&mut self, u: NodeId, f: &A) {
+        let node = &mut self.nodes[u];
+        node.value = A::act(f, &node.value, 1);
+        node.sum = A::act(f, &node.sum, node.size);
+        node.rev_sum = A::act(f, &node.rev_sum, node.size);
+        node.lazy = match node.lazy.take() {
+            Some(g) => Some(A::composition(f, &g)),
+            None => Some(f.clone()),
+        };
+    }
+
+    /// **O(1)**, propagate pending reverse and map to children
+    fn propagation(&mut self, u: NodeId) {
+        let (left, right) = (self.nodes[u].left, self.nodes[u].right);
+        if self.nodes[u].rev {
+            self.nodes[u].left = right;
+            self.nodes[u].right = left;
+            for child in left.into_iter().chain(right) {
+                self.reverse_node(child);
+            }
+            self.nodes[u].rev = false;
+        }
+        if let Some(f) = self.nodes[u].lazy.take() {
+            for child in left.into_iter().chain(right) {
+                self.apply_node(child, &f);
+            }
+        }
+    }
+
+    /// **O(1)**, recalculate size and aggregation from children
+    fn update(&mut self, u: NodeId) {
+        let (left, right) = (self.nodes[u].left, self.nodes[u].right);
+        let (left_sum, left_rev_sum, left_size) = match left {
+            Some(l) => {
+                (self.nodes[l].sum.clone(), self.nodes[l].rev_sum.clone(), self.nodes[l].size)
+            }
+            None => (M::identity(), M::identity(), 0),
+        };
+        let (right_sum, right_rev_sum, right_size) = match right {
+            Some(r) => {
+                (self.nodes[r].sum.clone(), self.nodes[r].rev_sum.clone(), self.nodes[r].size)
+            }
+            None => (M::identity(), M::identity(), 0),
+        };
+        let node = &mut self.nodes[u];
+        node.size = left_size + 1 + right_size;
+        node.sum = M::operation(&M::operation(&left_sum, &node.value), &right_sum);
+        node.rev_sum = M::operation(&M::operation(&right_rev_sum, &node.value), &left_rev_sum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::treap::implicit_treap::fixtures::{Add, Join, Sum};
+
+    #[test]
+    fn test_link_cut_connected() {
+        let mut lct = LinkCutTree::<i64, Sum>::new(&[0; 6]);
+        assert!(!lct.connected(0, 1));
+        assert!(lct.link(0, 1));
+        assert!(lct.link(1, 2));
+        assert!(lct.link(3, 4));
+        assert!(!lct.link(2, 0));
+        assert!(lct.connected(0, 2));
+        assert!(!lct.connected(0, 3));
+        assert!(lct.link(2, 3));
+        assert!(lct.connected(0, 4));
+        assert!(!lct.cut(0, 2));
+        assert!(lct.cut(1, 2));
+        assert!(!lct.cut(1, 2));
+        assert!(lct.connected(0, 1));
+        assert!(!lct.connected(1, 2));
+        assert!(lct.connected(2, 4));
+        assert!(!lct.connected(5, 4));
+    }
+
+    #[test]
+    fn test_lca_evert() {
+        //    0
+        //    |
+        //    1
+        //   / \
+        // 3-2 4-5
+        let mut lct = LinkCutTree::<i64, Sum>::new(&[0; 7]);
+        for &(u, v) in &[(1, 0), (2, 1), (3, 2), (4, 1), (5, 4)] {
+            lct.link(u, v);
+        }
+        lct.evert(0);
+        assert_eq!(lct.root(5), 0);
+        assert_eq!(lct.lca(3, 5), Some(1));
+        assert_eq!(lct.lca(3, 2), Some(2));
+        assert_eq!(lct.lca(0, 5), Some(0));
+        assert_eq!(lct.lca(6, 5), None);
+        lct.evert(3);
+        assert_eq!(lct.root(0), 3);
+        assert_eq!(lct.lca(0, 5), Some(1));
+        assert_eq!(lct.lca(4, 5), Some(4));
+        // path operations and cut keep the root
+        lct.query(0, 5);
+        lct.apply(2, 5, ());
+        assert_eq!(lct.root(0), 3);
+        assert_eq!(lct.lca(0, 5), Some(1));
+        assert!(lct.cut(4, 5));
+        assert_eq!(lct.root(0), 3);
+        assert_eq!(lct.root(5), 5);
+        assert_eq!(lct.lca(0, 4), Some(1));
+    }
+
+    #[test]
+    fn test_path_query_apply() {
+        let mut lct = LinkCutTree::<i64, Sum, Add>::new(&[1, 2, 4, 8, 16, 32]);
+        for &(u, v) in &[(0, 1), (1, 2), (1, 3), (3, 4)] {
+            lct.link(u, v);
+        }
+        assert_eq!(lct.query(0, 4), Some(1 + 2 + 8 + 16));
+        assert_eq!(lct.query(2, 4), Some(4 + 2 + 8 + 16));
+        assert_eq!(lct.query(2, 2), Some(4));
+        assert_eq!(lct.query(2, 5), None);
+        assert!(lct.apply(0, 3, Add(100)));
+        assert_eq!(lct.query(2, 4), Some(4 + 102 + 108 + 16));
+        assert_eq!(lct.get(0), 101);
+        lct.set(2, 0);
+        assert_eq!(lct.query(0, 2), Some(101 + 102));
+        assert!(!lct.apply(0, 5, Add(1)));
+    }
+
+    #[test]
+    fn test_path_non_commutative() {
+        let data: Vec<_> = "abcde".chars().map(|c| c.to_string()).collect();
+        let mut lct = LinkCutTree::<String, Join>::new(&data);
+        for &(u, v) in &[(0, 1), (1, 2), (2, 3), (1, 4)] {
+            lct.link(u, v);
+        }
+        assert_eq!(lct.query(0, 3), Some("abcd".to_string()));
+        assert_eq!(lct.query(3, 0), Some("dcba".to_string()));
+        assert_eq!(lct.query(4, 3), Some("ebcd".to_string()));
+        lct.evert(2);
+        assert_eq!(lct.query(3, 4), Some("dcbe".to_string()));
+    }
+
+    #[test]
+    fn test_random_forest() {
+        use rand::RngExt;
+        let n = 30;
+        let mut rng = rand::rng();
+        let values: Vec<i64> = (0..n).map(|_| rng.random_range(0..100)).collect();
+        let mut lct = LinkCutTree::<i64, Sum>::new(&values);
+        let mut edges = std::collections::HashSet::new();
+        // naive path sum by dfs on current edges
+        let path_sum = |edges: &std::collections::HashSet<(usize, usize)>, u: usize, v: usize| {
+            let mut stack = vec![(u, n, values[u])];
+            while let Some((x, from, sum)) = stack.pop() {
+                if x == v {
+                    return Some(sum);
+                }
+                for &(a, b) in edges.iter() {
+                    for &(p, q) in &[(a, b), (b, a)] {
+                        if p == x && q != from {
+                            stack.push((q, x, sum + values[q]));
+                        }
+                    }
+                }
+            }
+            None
+        };
+        for _ in 0..1000 {
+            let (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+            match rng.random_range(0..3) {
+                0 => {
+                    let linked = lct.link(u, v);
+                    assert_eq!(linked, path_sum(&edges, u, v).is_none());
+                    if linked {
+                        edges.insert((u, v));
+                    }
+                }
+                1 => {
+                    let cut = lct.cut(u, v);
+                    assert_eq!(cut, edges.remove(&(u, v)) || edges.remove(&(v, u)));
+                }
+                _ => assert_eq!(lct.query(u, v), path_sum(&edges, u, v)),
+            }
+        }
+    }
+}
@@ -1,7 +1,12 @@
+pub mod aho_corasick;
+pub mod binary_trie;
 pub mod counter;
 pub mod heap;
+pub mod link_cut_tree;
 pub mod linkedlist;
 pub mod segtree;
 pub mod sorted_vec;
+pub mod treap;
+pub mod trie_map;
 pub mod trie_tree;
 pub mod unionfind;
@@ -0,0 +1,598 @@
+use std::{
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    rc::Rc,
+};
+
+use rand::RngExt;
+
+use crate::collections::segtree::segtree::Monoid;
+
+/// lazy map over Monoid M, shared by sequence structures that update ranges lazily
+pub trait Action<M> {
+    /// identity map of Action
+    fn identity() -> Self;
+    /// composition of maps, (f ∘ g)(x) = f(g(x))
+    fn composition(f: &Self, g: &Self) -> Self;
+    /// apply map f to x, that is aggregation of len elements
+    fn act(f: &Self, x: &M, len: usize) -> M;
+}
+impl<M: Clone> Action<M> for () {
+    fn identity() -> Self {}
+    fn composition(_f: &Self, _g: &Self) -> Self {}
+    fn act(_f: &Self, x: &M, _len: usize) -> M {
+        x.clone()
+    }
+}
+
+type Link<M, A> = Option<Rc<Node<M, A>>>;
+
+#[derive(Clone)]
+struct Node<M, A> {
+    value: M,
+    sum: M,
+    rev_sum: M, // aggregation of reversed subtree, non-commutative monoid need it for reverse
+    len: usize,
+    rev: bool,       // children should be swapped (but this node's sum is already reversed)
+    lazy: Option<A>, // map that should be applied to children (None mean identity)
+    left: Link<M, A>,
+    right: Link<M, A>,
+}
+
+/// array-like sequence, nodes are shared with its clone and copied on write (persistent)
+pub struct ImplicitTreap<T, M, A = ()> {
+    root: Link<M, A>,
+    seed: u64,
+    element: PhantomData<T>,
+}
+impl<T, M, A> Clone for ImplicitTreap<T, M, A> {
+    /// **O(1)**, clone sequence, nodes are copied lazily when they are modified
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            seed: self.seed ^ 0x9e37_79b9_7f4a_7c15,
+            element: PhantomData,
+        }
+    }
+}
+impl<T, M, A> Default for ImplicitTreap<T, M, A>
+where
+    M: Monoid<T> + Clone,
+    A: Action<M> + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, M, A> FromIterator<T> for ImplicitTreap<T, M, A>
+where
+    M: Monoid<T> + Clone,
+    A: Action<M> + Clone,
+{
+    /// **O(n)**, make sequence from iterator
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut treap = Self::new();
+        let mut nodes: Vec<_> =
+            iter.into_iter().map(|x| Some(Rc::new(Node::new(M::from(x))))).collect();
+        // merge adjacent pair like bottom-up merge sort, so it take O(n) merge
+        while nodes.len() > 1 {
+            let mut merged = Vec::with_capacity(nodes.len().div_ceil(2));
+            let mut iter = nodes.into_iter();
+            while let Some(a) = iter.next() {
+                let b = iter.next().unwrap_or(None);
+                merged.push(treap.merge(a, b));
+            }
+            nodes = merged;
+        }
+        treap.root = nodes.pop().unwrap_or(None);
+        treap
+    }
+}
+
+impl<M: Clone, A: Action<M> + Clone> Node<M, A> {
+    /// **O(1)**, make leaf node
+    fn new(value: M) -> Self {
+        let (sum, rev_sum) = (value.clone(), value.clone());
+        Node { value, sum, rev_sum, len: 1, rev: false, lazy: None, left: None, right: None }
+    }
+}
+
+impl<T, M, A> ImplicitTreap<T, M, A>
+where
+    M: Monoid<T> + Clone,
+    A: Action<M> + Clone,
+{
+    /// **O(1)**, make empty sequence
+    pub fn new() -> Self {
+        Self { root: None, seed: rand::rng().random::<u64>() | 1, element: PhantomData }
+    }
+
+    /// **O(1)**, return the number of elements
+    pub fn len(&self) -> usize {
+        Self::size(&self.root)
+    }
+
+    /// **O(1)**, return true if the sequence has no element, else return false
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// **O(1)**, range to index half interval [left, right).
+    pub fn indices<R>(&self, range: R) -> (usize, usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let left = match range.start_bound() {
+            Bound::Unbounded => 0,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Included(&l) => l,
+        };
+        let right = match range.end_bound() {
+            Bound::Unbounded => self.len(),
+            Bound::Excluded(&r) => r.min(self.len()),
+            Bound::Included(&r) => (r + 1).min(self.len()),
+        };
+        assert!(left <= right);
+        (left, right)
+    }
+
+    /// **O(log(n))**, get i-th element
+    pub fn get(&self, i: usize) -> T {
+        assert!(i < self.len(), "index {} is out of 0..{}", i, self.len());
+        let (mut current, mut i, mut reversed, mut lazy) = (&self.root, i, false, None);
+        while let Some(node) = current {
+            let reversed_children = reversed ^ node.rev;
+            let (left, right) = match reversed_children {
+                true => (&node.right, &node.left),
+                false => (&node.left, &node.right),
+            };
+            let left_len = Self::size(left);
+            if i == left_len {
+                return match lazy {
+                    Some(f) => A::act(&f, &node.value, 1).into(),
+                    None => node.value.clone().into(),
+                };
+            }
+            lazy = Self::compose(lazy.as_ref(), node.lazy.as_ref());
+            reversed = reversed_children;
+            if i < left_len {
+                current = left;
+            } else {
+                current = right;
+                i -= left_len + 1;
+            }
+        }
+        unreachable!("index is checked to be smaller than size of tree")
+    }
+
+    /// **O(log(n))**, insert x as i-th element
+    pub fn insert(&mut self, i: usize, x: T) {
+        assert!(i <= self.len(), "index {} is out of 0..={}", i, self.len());
+        let (left, right) = Self::split(self.root.take(), i);
+        let middle = Some(Rc::new(Node::new(M::from(x))));
+        let left_middle = self.merge(left, middle);
+        self.root = self.merge(left_middle, right);
+    }
+
+    /// **O(log(n))**, add x to the end of the sequence
+    pub fn push(&mut self, x: T) {
+        self.insert(self.len(), x)
+    }
+
+    /// **O(log(n))**, remove i-th element and return it
+    pub fn remove(&mut self, i: usize) -> T {
+        assert!(i < self.len(), "index {} is out of 0..{}", i, self.len());
+        let (left, middle, right) = self.split3(i, i + 1);
+        self.merge3(left, None, right);
+        let node = middle.expect("range of size 1 has a node");
+        match Rc::try_unwrap(node) {
+            Ok(node) => node.value.into(),
+            Err(shared) => shared.value.clone().into(),
+        }
+    }
+
+    /// **O(log(n))**, split the sequence into [0, at) and [at, len), and return latter
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len(), "index {} is out of 0..={}", at, self.len());
+        let (left, right) = Self::split(self.root.take(), at);
+        self.root = left;
+        Self { root: right, seed: self.random(), element: PhantomData }
+    }
+
+    /// **O(log(n))**, concatenate the other sequence to the end of this sequence
+    pub fn append(&mut self, other: &mut Self) {
+        let (left, right) = (self.root.take(), other.root.take());
+        self.root = self.merge(left, right);
+    }
+
+    /// **O(log(n))**, calculate aggregation of range
+    pub fn query<R>(&mut self, range: R) -> T
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = self.indices(range);
+        let (left, middle, right) = self.split3(l, r);
+        let result = Self::sum(&middle);
+        self.merge3(left, middle, right);
+        result.into()
+    }
+
+    /// **O(log(n))**, reverse range in place
+    pub fn reverse<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = self.indices(range);
+        let (left, mut middle, right) = self.split3(l, r);
+        if let Some(node) = middle.as_mut() {
+            Self::reverse_node(node);
+        }
+        self.merge3(left, middle, right);
+    }
+
+    /// **O(log(n))**, apply map f to all elements in range
+    pub fn apply<R>(&mut self, range: R, f: A)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = self.indices(range);
+        let (left, mut middle, right) = self.split3(l, r);
+        if let Some(node) = middle.as_mut() {
+            Self::apply_node(node, &f);
+        }
+        self.merge3(left, middle, right);
+    }
+
+    /// **O(log(n))**, copy range as new sequence, nodes are shared until they are modified
+    pub fn copy_range<R>(&mut self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = self.indices(range);
+        let (left, middle, right) = self.split3(l, r);
+        let copied = Self { root: middle.clone(), seed: self.random(), element: PhantomData };
+        self.merge3(left, middle, right);
+        copied
+    }
+
+    /// **O(n)**, collect all elements into vec
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len());
+        Self::collect(&self.root, false, None, &mut result);
+        result
+    }
+
+    /// **O(n)**, in-order traversal with pending reverse and map
+    fn collect(node: &Link<M, A>, reversed: bool, lazy: Option<&A>, result: &mut Vec<T>) {
+        if let Some(node) = node {
+            let reversed_children = reversed ^ node.rev;
+            let (left, right) = match reversed_children {
+                true => (&node.right, &node.left),
+                false => (&node.left, &node.right),
+            };
+            let children_lazy = Self::compose(lazy, node.lazy.as_ref());
+            Self::collect(left, reversed_children, children_lazy.as_ref(), result);
+            result.push(match lazy {
+                Some(f) => A::act(f, &node.value, 1).into(),
+                None => node.value.clone().into(),
+            });
+            Self::collect(right, reversed_children, children_lazy.as_ref(), result);
+        }
+    }
+
+    /// **O(1)**, xorshift for merge
+    fn random(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+
+    /// **O(1)**, number of nodes of the subtree
+    fn size(node: &Link<M, A>) -> usize {
+        node.as_ref().map_or(0, |n| n.len)
+    }
+
+    /// **O(1)**, aggregation of the subtree
+    fn sum(node: &Link<M, A>) -> M {
+        node.as_ref().map_or_else(M::identity, |n| n.sum.clone())
+    }
+
+    /// **O(1)**, aggregation of the reversed subtree
+    fn rev_sum(node: &Link<M, A>) -> M {
+        node.as_ref().map_or_else(M::identity, |n| n.rev_sum.clone())
+    }
+
+    /// **O(1)**, composition of maps, None mean identity map
+    fn compose(f: Option<&A>, g: Option<&A>) -> Option<A> {
+        match (f, g) {
+            (Some(f), Some(g)) => Some(A::composition(f, g)),
+            (Some(f), None) => Some(f.clone()),
+            (None, Some(g)) => Some(g.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// **O(1)**, reverse the subtree lazily
+    fn reverse_node(node: &mut Rc<Node<M, A>>) {
+        let node = Rc::make_mut(node);
+        std::mem::swap(&mut node.sum, &mut node.rev_sum);
+        node.rev ^= true;
+    }
+
+    /// **O(1)**, apply map to the subtree lazily
+    fn apply_node(node: &mut Rc<Node<M, A>>, f: &A) {
+        let node = Rc::make_mut(node);
+        node.value = A::act(f, &node.value, 1);
+        node.sum = A::act(f, &node.sum, node.len);
+        node.rev_sum = A::act(f, &node.rev_sum, node.len);
+        node.lazy = Self::compose(Some(f), node.lazy.as_ref());
+    }
+
+    /// **O(1)**, propagate pending reverse and map to children
+    fn propagation(node: &mut Rc<Node<M, A>>) {
+        if !node.rev && node.lazy.is_none() {
+            return;
+        }
+        let node = Rc::make_mut(node);
+        if node.rev {
+            std::mem::swap(&mut node.left, &mut node.right);
+            for child in [&mut node.left, &mut node.right].iter_mut().filter_map(|c| c.as_mut()) {
+                Self::reverse_node(child);
+            }
+            node.rev = false;
+        }
+        if let Some(f) = node.lazy.take() {
+            for child in [&mut node.left, &mut node.right].iter_mut().filter_map(|c| c.as_mut()) {
+                Self::apply_node(child, &f);
+            }
+        }
+    }
+
+    /// **O(1)**, recalculate size and aggregation from children
+    fn update(node: &mut Rc<Node<M, A>>) {
+        let node = Rc::make_mut(node);
+        node.len = Self::size(&node.left) + 1 + Self::size(&node.right);
+        let left_value = M::operation(&Self::sum(&node.left), &node.value);
+        node.sum = M::operation(&left_value, &Self::sum(&node.right));
+        let right_value = M::operation(&Self::rev_sum(&node.right), &node.value);
+        node.rev_sum = M::operation(&right_value, &Self::rev_sum(&node.left));
+    }
+
+    /// **O(log(n))**, split the subtree into first k elements and the others
+    fn split(node: Link<M, A>, k: usize) -> (Link<M, A>, Link<M, A>) {
+        match node {
+            None => (None, None),
+            Some(mut node) => {
+                Self::propagation(&mut node);
+                let left_len = Self::size(&node.left);
+                let n = Rc::make_mut(&mut node);
+                if k <= left_len {
+                    let (left, right) = Self::split(n.left.take(), k);
+                    n.left = right;
+                    Self::update(&mut node);
+                    (left, Some(node))
+                } else {
+                    let (left, right) = Self::split(n.right.take(), k - left_len - 1);
+                    n.right = left;
+                    Self::update(&mut node);
+                    (Some(node), right)
+                }
+            }
+        }
+    }
+
+    /// **O(log(n))**, concatenate two subtrees, root is chosen randomly by their size
+    fn merge(&mut self, a: Link<M, A>, b: Link<M, A>) -> Link<M, A> {
+        match (a, b) {
+            (None, b) => b,
+            (a, None) => a,
+            (Some(mut a), Some(mut b)) => {
+                // choosing by size instead of priority keep balance even if nodes are shared
+                if (self.random() % (a.len + b.len) as u64) < a.len as u64 {
+                    Self::propagation(&mut a);
+                    let n = Rc::make_mut(&mut a);
+                    n.right = self.merge(n.right.take(), Some(b));
+                    Self::update(&mut a);
+                    Some(a)
+                } else {
+                    Self::propagation(&mut b);
+                    let n = Rc::make_mut(&mut b);
+                    n.left = self.merge(Some(a), n.left.take());
+                    Self::update(&mut b);
+                    Some(b)
+                }
+            }
+        }
+    }
+
+    /// **O(log(n))**, split the tree into [0, l), [l, r) and [r, len)
+    fn split3(&mut self, l: usize, r: usize) -> (Link<M, A>, Link<M, A>, Link<M, A>) {
+        let (left_middle, right) = Self::split(self.root.take(), r);
+        let (left, middle) = Self::split(left_middle, l);
+        (left, middle, right)
+    }
+
+    /// **O(log(n))**, merge three subtrees as the tree
+    fn merge3(&mut self, left: Link<M, A>, middle: Link<M, A>, right: Link<M, A>) {
+        let left_middle = self.merge(left, middle);
+        self.root = self.merge(left_middle, right);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::{Add, Join, Sum};
+    use super::*;
+
+    #[test]
+    fn test_empty_treap() {
+        let mut treap = ImplicitTreap::<i64, Sum>::new();
+        assert_eq!(treap.len(), 0);
+        assert!(treap.is_empty());
+        assert_eq!(treap.query(..), 0);
+        assert_eq!(treap.to_vec(), Vec::<i64>::new());
+        treap.reverse(..);
+        assert_eq!(treap.split_off(0).len(), 0);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut treap: ImplicitTreap<_, Sum> = vec![1, 2, 3, 4, 5].into_iter().collect();
+        assert_eq!(treap.to_vec(), vec![1, 2, 3, 4, 5]);
+        treap.insert(0, 10);
+        treap.insert(3, 20);
+        treap.push(30);
+        assert_eq!(treap.to_vec(), vec![10, 1, 2, 20, 3, 4, 5, 30]);
+        assert_eq!(treap.remove(3), 20);
+        assert_eq!(treap.remove(0), 10);
+        assert_eq!(treap.to_vec(), vec![1, 2, 3, 4, 5, 30]);
+        assert_eq!(treap.get(5), 30);
+        assert_eq!(treap.query(1..4), 9);
+        assert_eq!(treap.query(..), 45);
+    }
+
+    #[test]
+    fn test_split_append_reverse() {
+        let mut treap: ImplicitTreap<_, Join> = "rustabc".chars().map(|c| c.to_string()).collect();
+        let mut abc = treap.split_off(4);
+        assert_eq!(treap.query(..), "rust");
+        assert_eq!(abc.query(..), "abc");
+        abc.append(&mut treap);
+        assert_eq!(abc.query(..), "abcrust");
+        assert!(treap.is_empty());
+        abc.reverse(3..);
+        assert_eq!(abc.query(..), "abctsur");
+        assert_eq!(abc.query(2..5), "cts");
+        abc.reverse(..);
+        assert_eq!(abc.query(..), "rustcba");
+        assert_eq!(abc.get(4), "c");
+    }
+
+    #[test]
+    fn test_lazy_apply() {
+        let mut treap: ImplicitTreap<_, Sum, Add> = (0..10).collect();
+        treap.apply(2..5, Add(10));
+        assert_eq!(treap.to_vec(), vec![0, 1, 12, 13, 14, 5, 6, 7, 8, 9]);
+        assert_eq!(treap.query(..), 75);
+        treap.reverse(3..8);
+        assert_eq!(treap.to_vec(), vec![0, 1, 12, 7, 6, 5, 14, 13, 8, 9]);
+        treap.apply(..4, Add(-1));
+        assert_eq!(treap.query(..4), 16);
+        assert_eq!(treap.get(3), 6);
+    }
+
+    #[test]
+    fn test_persistent_copy() {
+        let mut treap: ImplicitTreap<_, Sum, Add> = (0..6).collect();
+        let snapshot = treap.clone();
+        let mut copied = treap.copy_range(1..4);
+        treap.apply(.., Add(100));
+        copied.reverse(..);
+        assert_eq!(snapshot.to_vec(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(treap.to_vec(), vec![100, 101, 102, 103, 104, 105]);
+        assert_eq!(copied.to_vec(), vec![3, 2, 1]);
+        // paste the copy of head to the end repeatedly
+        for _ in 0..10 {
+            let mut pasted = copied.copy_range(..2);
+            copied.append(&mut pasted);
+        }
+        assert_eq!(copied.len(), 3 + 2 * 10);
+        assert_eq!(copied.query(..), 6 + 5 * 10);
+        copied.apply(..2, Add(1));
+        assert_eq!(copied.query(..), 6 + 5 * 10 + 2);
+        assert_eq!(copied.get(3), 3);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut rng = rand::rng();
+        let mut treap = ImplicitTreap::<i64, Sum, Add>::new();
+        let mut v: Vec<i64> = Vec::new();
+        for _ in 0..2000 {
+            let (l, r) = {
+                let (a, b) = (rng.random_range(0..=v.len()), rng.random_range(0..=v.len()));
+                (a.min(b), a.max(b))
+            };
+            match rng.random_range(0..5) {
+                0 => {
+                    let x = rng.random_range(-100..100);
+                    treap.insert(l, x);
+                    v.insert(l, x);
+                }
+                1 if l < v.len() => assert_eq!(treap.remove(l), v.remove(l)),
+                2 => {
+                    treap.reverse(l..r);
+                    v[l..r].reverse();
+                }
+                3 => {
+                    let x = rng.random_range(-100..100);
+                    treap.apply(l..r, Add(x));
+                    v[l..r].iter_mut().for_each(|vi| *vi += x);
+                }
+                _ => assert_eq!(treap.query(l..r), v[l..r].iter().sum::<i64>()),
+            }
+        }
+        assert_eq!(treap.to_vec(), v);
+    }
+}
+
+/// monoid and action used by tests of lazy sequence structures
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::Action;
+    use crate::collections::segtree::segtree::Monoid;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Sum(pub i64);
+    impl Monoid<i64> for Sum {
+        fn identity() -> Self {
+            Self(0)
+        }
+        fn operation(a: &Self, b: &Self) -> Self {
+            Self(a.0 + b.0)
+        }
+        fn into(self) -> i64 {
+            self.0
+        }
+    }
+    impl From<i64> for Sum {
+        fn from(a: i64) -> Self {
+            Self(a)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Add(pub i64);
+    impl Action<Sum> for Add {
+        fn identity() -> Self {
+            Self(0)
+        }
+        fn composition(f: &Self, g: &Self) -> Self {
+            Self(f.0 + g.0)
+        }
+        fn act(f: &Self, x: &Sum, len: usize) -> Sum {
+            Sum(x.0 + f.0 * len as i64)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Join(pub String);
+    impl Monoid<String> for Join {
+        fn identity() -> Self {
+            Join("".to_string())
+        }
+        fn operation(a: &Self, b: &Self) -> Self {
+            Join(format!("{}{}", a.0, b.0))
+        }
+        fn into(self) -> String {
+            self.0
+        }
+    }
+    impl From<String> for Join {
+        fn from(a: String) -> Self {
+            Self(a)
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod implicit_treap;
@@ -0,0 +1,416 @@
+use std::{collections::HashMap, hash::Hash};
+
+#[derive(Debug)]
+pub struct TrieMap<E, V> {
+    pub(super) root: TrieNode<E, V>,
+    len: usize,
+}
+#[derive(Debug)]
+pub(super) struct TrieNode<E, V> {
+    pub(super) children: HashMap<E, Self>,
+    pub(super) value: Option<V>, // value of the key that end at this node
+    prefix_count: usize,         // number of keys that pass this node
+}
+impl<E: Hash + Eq, V> TrieNode<E, V> {
+    /// **O(1)**, make node that has no child
+    fn new() -> Self {
+        TrieNode { children: HashMap::new(), value: None, prefix_count: 0 }
+    }
+}
+
+/// view into a key of the trie map, which may be inserted or not
+pub struct Entry<'a, E, V> {
+    key: Vec<E>,
+    slot: Slot<'a, E, V>,
+}
+/// located value if the key is inserted, else the map to insert into
+enum Slot<'a, E, V> {
+    Occupied(&'a mut V),
+    Vacant(&'a mut TrieMap<E, V>),
+}
+impl<'a, E, V> Entry<'a, E, V>
+where
+    E: Hash + Eq + Clone,
+{
+    /// **O(1)**, key of this entry
+    pub fn key(&self) -> &[E] {
+        &self.key
+    }
+
+    /// **O(1)** if the key is inserted else **O(m)**, insert default if the key is not inserted,
+    /// and return mutable reference to the value
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// **O(1)** if the key is inserted else **O(m)**, insert default() if the key is not inserted,
+    /// and return mutable reference to the value
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self.slot {
+            Slot::Occupied(value) => value,
+            Slot::Vacant(map) => map.insert_new(self.key, default()),
+        }
+    }
+
+    /// **O(1)**, modify the value if the key is inserted
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Slot::Occupied(value) = &mut self.slot {
+            f(value);
+        }
+        self
+    }
+}
+impl<'a, E, V> Entry<'a, E, V>
+where
+    E: Hash + Eq + Clone,
+    V: Default,
+{
+    /// **O(1)** if the key is inserted else **O(m)**, insert V::default() if the key is not inserted,
+    /// and return mutable reference to the value
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// iterator over (key, value) in lexicographic order of key, made by pairs_with_prefix
+pub struct PairsWithPrefix<'a, E, V> {
+    key: Vec<E>,
+    // (length of key above the node, edge to the node, node) in reverse order of visit
+    stack: Vec<(usize, Option<&'a E>, &'a TrieNode<E, V>)>,
+}
+impl<'a, E, V> Iterator for PairsWithPrefix<'a, E, V>
+where
+    E: Hash + Eq + Clone + Ord,
+{
+    type Item = (Vec<E>, &'a V);
+    /// **O(|children| log(|E|))** for each visited node, dfs in order of children's key
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((depth, edge, node)) = self.stack.pop() {
+            self.key.truncate(depth);
+            self.key.extend(edge.cloned());
+            let mut children: Vec<_> = node.children.iter().collect();
+            children.sort_by(|a, b| b.0.cmp(a.0));
+            let depth = self.key.len();
+            self.stack.extend(children.into_iter().map(|(k, child)| (depth, Some(k), child)));
+            if let Some(value) = node.value.as_ref() {
+                return Some((self.key.clone(), value));
+            }
+        }
+        None
+    }
+}
+
+impl<E, V> Default for TrieMap<E, V>
+where
+    E: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, V> TrieMap<E, V>
+where
+    E: Hash + Eq + Clone,
+{
+    /// **O(1)**, make empty trie map
+    pub fn new() -> Self {
+        TrieMap { root: TrieNode::new(), len: 0 }
+    }
+
+    /// **O(1)**, return the number of inserted keys
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// **O(1)**, return true if the trie has no key, else return false
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// **O(m)**, search prefix and return node and remain keys
+    fn search<K>(&self, key: K) -> (&TrieNode<E, V>, Option<E>, K::IntoIter)
+    where
+        K: IntoIterator<Item = E>,
+    {
+        let mut current = &self.root;
+        let mut iter = key.into_iter();
+        while let Some(k) = iter.next() {
+            if let Some(next) = current.children.get(&k) {
+                current = next;
+            } else {
+                return (current, Some(k), iter);
+            }
+        }
+        (current, None, iter)
+    }
+
+    /// **O(m)**, search prefix and return mutable node and remain keys, visit is called with passed nodes
+    fn search_mut<K, F>(
+        &mut self,
+        key: K,
+        mut visit: F,
+    ) -> (&mut TrieNode<E, V>, Option<E>, K::IntoIter)
+    where
+        K: IntoIterator<Item = E>,
+        F: FnMut(&mut TrieNode<E, V>),
+    {
+        let mut current = &mut self.root;
+        visit(current);
+        let mut iter = key.into_iter();
+        while let Some(k) = iter.next() {
+            // check before get_mut, returning borrowed node in the loop is rejected by borrow checker
+            if !current.children.contains_key(&k) {
+                return (current, Some(k), iter);
+            }
+            current = current.children.get_mut(&k).expect("above checked");
+            visit(current);
+        }
+        (current, None, iter)
+    }
+
+    /// **O(m)**, insert key with value, if the key is already inserted then return old value
+    pub fn insert<K>(&mut self, key: K, value: V) -> Option<V>
+    where
+        K: IntoIterator<Item = E>,
+    {
+        let key: Vec<_> = key.into_iter().collect();
+        if let Some(old) = self.get_mut(key.iter().cloned()) {
+            return Some(std::mem::replace(old, value));
+        }
+        self.insert_new(key, value);
+        None
+    }
+
+    /// **O(m)**, insert key that is not inserted yet, and return mutable reference to the value
+    fn insert_new(&mut self, key: Vec<E>, value: V) -> &mut V {
+        self.len += 1;
+        let (mut current, head, remain) = self.search_mut(key, |node| node.prefix_count += 1);
+        for key_element in head.into_iter().chain(remain) {
+            current = current.children.entry(key_element).or_insert_with(TrieNode::new);
+            current.prefix_count += 1;
+        }
+        current.value.get_or_insert(value)
+    }
+
+    /// **O(m)**, get entry of the key for in-place manipulation, the key is searched only here
+    pub fn entry<K>(&mut self, key: K) -> Entry<'_, E, V>
+    where
+        K: IntoIterator<Item = E>,
+    {
+        let key: Vec<_> = key.into_iter().collect();
+        // check before get_mut, returning borrowed value conditionally is rejected by borrow checker
+        let slot = if self.contains_key(key.iter().cloned()) {
+            Slot::Occupied(self.get_mut(key.iter().cloned()).expect("above checked"))
+        } else {
+            Slot::Vacant(self)
+        };
+        Entry { key, slot }
+    }
+
+    /// **O(m)**, remove key and prune dead branch, and return its value
+    pub fn remove<K>(&mut self, key: K) -> Option<V>
+    where
+        K: IntoIterator<Item = E>,
+    {
+        let key: Vec<_> = key.into_iter().collect();
+        if !self.contains_key(key.iter().cloned()) {
+            return None;
+        }
+        self.len -= 1;
+        let mut current = &mut self.root;
+        current.prefix_count -= 1;
+        for k in key {
+            if current.children[&k].prefix_count == 1 {
+                // only this key pass the child, so whole branch is dead and value is at its end
+                let mut dead = current.children.remove(&k).expect("key is inserted");
+                while dead.value.is_none() {
+                    dead = dead.children.into_iter().next().expect("key is inserted").1;
+                }
+                return dead.value;
+            }
+            current = current.children.get_mut(&k).expect("key is inserted");
+            current.prefix_count -= 1;
+        }
+        current.value.take()
+    }
+
+    /// **O(m)**, judge key is inserted or not
+    pub fn contains_key<K>(&self, key: K) -> bool
+    where
+        K: IntoIterator<Item = E>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// **O(m)**, get reference to the value of the key
+    pub fn get<K>(&self, key: K) -> Option<&V>
+    where
+        K: IntoIterator<Item = E>,
+    {
+        match self.search(key) {
+            (current, None, _) => current.value.as_ref(),
+            (_, Some(_), _) => None,
+        }
+    }
+
+    /// **O(m)**, get mutable reference to the value of the key
+    pub fn get_mut<K>(&mut self, key: K) -> Option<&mut V>
+    where
+        K: IntoIterator<Item = E>,
+    {
+        match self.search_mut(key, |_| ()) {
+            (current, None, _) => current.value.as_mut(),
+            (_, Some(_), _) => None,
+        }
+    }
+
+    /// **O(m)**, count the number of inserted keys that start with prefix
+    pub fn count_with_prefix<K>(&self, prefix: K) -> usize
+    where
+        K: IntoIterator<Item = E>,
+    {
+        match self.search(prefix) {
+            (current, None, _) => current.prefix_count,
+            (_, Some(_), _) => 0,
+        }
+    }
+
+    /// **O(m)**, longest common prefix of all inserted keys
+    pub fn longest_common_prefix(&self) -> Vec<E> {
+        let (mut current, mut prefix) = (&self.root, Vec::new());
+        while current.value.is_none() && current.children.len() == 1 {
+            let (k, next) = current.children.iter().next().expect("only one child");
+            prefix.push(k.clone());
+            current = next;
+        }
+        prefix
+    }
+
+    /// **O(m)**, find the longest inserted key that is prefix of the key, and return its length and value
+    pub fn longest_prefix_match<K>(&self, key: K) -> Option<(usize, &V)>
+    where
+        K: IntoIterator<Item = E>,
+    {
+        let mut current = &self.root;
+        let mut matched = current.value.as_ref().map(|v| (0, v));
+        for (i, k) in key.into_iter().enumerate() {
+            match current.children.get(&k) {
+                Some(next) => current = next,
+                None => break,
+            }
+            if let Some(v) = current.value.as_ref() {
+                matched = Some((i + 1, v));
+            }
+        }
+        matched
+    }
+}
+
+impl<E, V> TrieMap<E, V>
+where
+    E: Hash + Eq + Clone + Ord,
+{
+    /// **O(m)**, iterator over all inserted (key, value) that key start with prefix in lexicographic order
+    pub fn pairs_with_prefix<K>(&self, prefix: K) -> PairsWithPrefix<'_, E, V>
+    where
+        K: IntoIterator<Item = E>,
+    {
+        let key: Vec<_> = prefix.into_iter().collect();
+        let stack = match self.search(key.iter().cloned()) {
+            (current, None, _) => vec![(key.len(), None, current)],
+            (_, Some(_), _) => Vec::new(),
+        };
+        PairsWithPrefix { key, stack }
+    }
+
+    /// **O(1)**, iterator over all inserted (key, value) in lexicographic order of key
+    pub fn pairs(&self) -> PairsWithPrefix<'_, E, V> {
+        self.pairs_with_prefix(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get() {
+        let mut map = TrieMap::new();
+        assert_eq!(map.insert("rust".chars(), 1), None);
+        assert_eq!(map.insert("ruby".chars(), 2), None);
+        assert_eq!(map.insert("ru".chars(), 3), None);
+        assert_eq!(map.insert("rust".chars(), 4), Some(1));
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("rust".chars()), Some(&4));
+        assert_eq!(map.get("rus".chars()), None);
+        assert_eq!(map.get("rusty".chars()), None);
+        *map.get_mut("ru".chars()).unwrap() += 10;
+        assert_eq!(map.get("ru".chars()), Some(&13));
+        assert!(map.get_mut("r".chars()).is_none());
+        assert_eq!(map.count_with_prefix("ru".chars()), 3);
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map = TrieMap::new();
+        for word in "the quick fox jumps over the lazy dog the end".split_whitespace() {
+            *map.entry(word.chars()).or_insert(0) += 1;
+        }
+        assert_eq!(map.get("the".chars()), Some(&3));
+        assert_eq!(map.get("fox".chars()), Some(&1));
+        map.entry("fox".chars()).and_modify(|c| *c *= 10).or_default();
+        map.entry("cat".chars()).and_modify(|c| *c *= 10).or_default();
+        assert_eq!(map.get("fox".chars()), Some(&10));
+        assert_eq!(map.get("cat".chars()), Some(&0));
+        assert_eq!(map.entry("dog".chars()).key(), ['d', 'o', 'g']);
+        assert_eq!(map.len(), 9);
+        assert_eq!(map.count_with_prefix("c".chars()), 1);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = TrieMap::new();
+        map.insert("rust".chars(), 1);
+        map.insert("ruby".chars(), 2);
+        map.insert("ru".chars(), 3);
+        assert_eq!(map.remove("rub".chars()), None);
+        assert_eq!(map.remove("ruby".chars()), Some(2));
+        assert_eq!(map.remove("ruby".chars()), None);
+        assert_eq!(map.remove("ru".chars()), Some(3));
+        assert_eq!(map.get("rust".chars()), Some(&1));
+        assert_eq!(map.remove("rust".chars()), Some(1));
+        assert!(map.is_empty());
+        assert!(map.root.children.is_empty());
+    }
+
+    #[test]
+    fn test_longest_prefix_match() {
+        let mut map = TrieMap::new();
+        map.insert("a".chars(), "A");
+        map.insert("ab".chars(), "AB");
+        map.insert("abcd".chars(), "ABCD");
+        assert_eq!(map.longest_prefix_match("abc".chars()), Some((2, &"AB")));
+        assert_eq!(map.longest_prefix_match("abcde".chars()), Some((4, &"ABCD")));
+        assert_eq!(map.longest_prefix_match("a".chars()), Some((1, &"A")));
+        assert_eq!(map.longest_prefix_match("b".chars()), None);
+        map.insert("".chars(), "");
+        assert_eq!(map.longest_prefix_match("b".chars()), Some((0, &"")));
+    }
+
+    #[test]
+    fn test_pairs_with_prefix() {
+        let mut map = TrieMap::new();
+        for (i, word) in ["rust", "ruby", "ru", "python", "perl"].iter().enumerate() {
+            map.insert(word.bytes(), i);
+        }
+        assert_eq!(
+            map.pairs_with_prefix("ru".bytes()).collect::<Vec<_>>(),
+            vec![(b"ru".to_vec(), &2), (b"ruby".to_vec(), &1), (b"rust".to_vec(), &0)]
+        );
+        assert_eq!(map.pairs().count(), 5);
+        assert_eq!(map.pairs().next(), Some((b"perl".to_vec(), &4)));
+        assert_eq!(map.pairs_with_prefix("java".bytes()).next(), None);
+        assert_eq!(map.pairs_with_prefix("rust".bytes()).count(), 1);
+    }
+}
@@ -1,13 +1,19 @@
-use std::{collections::HashMap, hash::Hash};
+use crate::collections::trie_map::TrieMap;
+use std::hash::Hash;
 
+/// set of keys, which is trie map whose values are empty
 #[derive(Debug)]
 pub struct TrieTree<E> {
-    root: TrieNode<E>,
-    len: usize,
+    map: TrieMap<E, ()>,
 }
-#[derive(Debug)]
-struct TrieNode<E> {
-    children: HashMap<E, Self>,
+
+impl<E> Default for TrieTree<E>
+where
+    E: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<E> TrieTree<E>
@@ -16,81 +22,72 @@ where
 {
     /// **O(1)**, make empty trie tree
     pub fn new() -> Self {
-        let root = TrieNode { children: HashMap::new() };
-        TrieTree { root, len: 0 }
+        TrieTree { map: TrieMap::new() }
+    }
+
+    /// **O(1)**, return the number of inserted keys
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// **O(1)**, return true if the trie has no key, else return false
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
     }
 
-    /// **O(m)**, search prefix and return node and remain keys
-    fn search<K>(&self, key: K) -> (&TrieNode<E>, Option<E>, K::IntoIter)
+    /// **O(m)**, insert key, if the key is already inserted then return false
+    pub fn insert<K>(&mut self, key: K) -> bool
     where
         K: IntoIterator<Item = E>,
     {
-        let mut current = &self.root;
-        let mut iter = key.into_iter();
-        while let Some(k) = iter.next() {
-            if let Some(next) = current.children.get(&k) {
-                current = next;
-            } else {
-                return (current, Some(k), iter);
-            }
-        }
-        (current, None, iter)
+        self.map.insert(key, ()).is_none()
     }
 
-    /// **O(m)**, search prefix and return mutable node and remain keys
-    fn search_mut<K>(&mut self, key: K) -> (&mut TrieNode<E>, Option<E>, K::IntoIter)
+    /// **O(m)**, remove key and prune dead branch, if the key is not inserted then return false
+    pub fn remove<K>(&mut self, key: K) -> bool
     where
         K: IntoIterator<Item = E>,
     {
-        // TODO: without raw pointer and unsafe ... ?
-        let mut current = &mut self.root as *mut TrieNode<E>;
-        let mut iter = key.into_iter();
-        while let Some(k) = iter.next() {
-            if let Some(next) = unsafe { &mut *current }.children.get_mut(&k) {
-                current = next;
-            } else {
-                return (unsafe { &mut *current }, Some(k), iter);
-            }
-        }
-        (unsafe { &mut *current }, None, iter)
+        self.map.remove(key).is_some()
     }
 
-    /// **O(m)**, insert key
-    pub fn insert<K>(&mut self, key: K) -> bool
+    /// **O(m)**, judge key is inserted or not
+    pub fn contains<K>(&self, key: K) -> bool
     where
         K: IntoIterator<Item = E>,
     {
-        let (mut current, head, remain) = self.search_mut(key);
-        match head {
-            Some(h) => {
-                for key_element in vec![h].into_iter().chain(remain) {
-                    current = current
-                        .children
-                        .entry(key_element)
-                        .or_insert_with(|| TrieNode { children: HashMap::new() })
-                }
-                self.len += 1;
-                true
-            }
-            None => false,
-        }
+        self.map.contains_key(key)
     }
 
-    /// **O(m)**, remove key (WARNING: unimplemented!)
-    pub fn remove<K>(&mut self, _key: K) -> bool
+    /// **O(m)**, count the number of inserted keys that start with prefix
+    pub fn count_with_prefix<K>(&self, prefix: K) -> usize
     where
         K: IntoIterator<Item = E>,
     {
-        todo!()
+        self.map.count_with_prefix(prefix)
     }
 
-    /// **O(m)**, judge key is inserted or not
-    pub fn contains<K>(&self, key: K) -> bool
+    /// **O(m)**, longest common prefix of all inserted keys
+    pub fn longest_common_prefix(&self) -> Vec<E> {
+        self.map.longest_common_prefix()
+    }
+}
+
+impl<E> TrieTree<E>
+where
+    E: Hash + Eq + Clone + Ord,
+{
+    /// **O(m)**, iterator over all inserted keys that start with prefix in lexicographic order
+    pub fn keys_with_prefix<K>(&self, prefix: K) -> impl Iterator<Item = Vec<E>> + '_
     where
         K: IntoIterator<Item = E>,
     {
-        let (current, head, _) = self.search(key);
-        current.children.is_empty() && head.is_none()
+        self.map.pairs_with_prefix(prefix).map(|(key, _)| key)
+    }
+
+    /// **O(1)**, iterator over all inserted keys in lexicographic order
+    pub fn keys(&self) -> impl Iterator<Item = Vec<E>> + '_ {
+        self.keys_with_prefix(Vec::new())
     }
 }
 
@@ -111,4 +108,80 @@ mod tests {
         assert!(!trie.contains("ru".chars()));
         println!("{:?}", trie);
     }
+
+    #[test]
+    fn test_insert_prefix() {
+        let mut trie = TrieTree::<char>::new();
+        assert!(trie.insert("rust".chars()));
+        assert!(trie.insert("ru".chars()));
+        assert!(!trie.insert("rust".chars()));
+        assert!(trie.insert("".chars()));
+        assert!(trie.contains("ru".chars()));
+        assert!(trie.contains("".chars()));
+        assert!(!trie.contains("rus".chars()));
+        assert_eq!(trie.len(), 3);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut trie = TrieTree::<char>::new();
+        trie.insert("rust".chars());
+        trie.insert("ruby".chars());
+        trie.insert("ru".chars());
+        assert!(!trie.remove("rub".chars()));
+        assert!(trie.remove("ruby".chars()));
+        assert!(!trie.remove("ruby".chars()));
+        assert!(!trie.contains("ruby".chars()));
+        assert!(trie.contains("rust".chars()));
+        assert_eq!(trie.count_with_prefix("rub".chars()), 0);
+        assert!(trie.remove("ru".chars()));
+        assert!(trie.contains("rust".chars()));
+        assert!(trie.remove("rust".chars()));
+        assert!(trie.is_empty());
+        assert!(trie.map.root.children.is_empty());
+    }
+
+    #[test]
+    fn test_count_with_prefix() {
+        let mut trie = TrieTree::<char>::new();
+        for word in &["rust", "ruby", "ru", "python", "perl"] {
+            trie.insert(word.chars());
+        }
+        assert_eq!(trie.count_with_prefix("".chars()), 5);
+        assert_eq!(trie.count_with_prefix("r".chars()), 3);
+        assert_eq!(trie.count_with_prefix("ru".chars()), 3);
+        assert_eq!(trie.count_with_prefix("rus".chars()), 1);
+        assert_eq!(trie.count_with_prefix("p".chars()), 2);
+        assert_eq!(trie.count_with_prefix("java".chars()), 0);
+    }
+
+    #[test]
+    fn test_keys_with_prefix() {
+        let mut trie = TrieTree::<char>::new();
+        for word in &["rust", "ruby", "ru", "python", "perl"] {
+            trie.insert(word.chars());
+        }
+        let to_strings = |keys: &mut dyn Iterator<Item = Vec<char>>| -> Vec<String> {
+            keys.map(|k| k.into_iter().collect()).collect()
+        };
+        assert_eq!(to_strings(&mut trie.keys()), ["perl", "python", "ru", "ruby", "rust"]);
+        assert_eq!(to_strings(&mut trie.keys_with_prefix("ru".chars())), ["ru", "ruby", "rust"]);
+        assert_eq!(to_strings(&mut trie.keys_with_prefix("rust".chars())), ["rust"]);
+        assert_eq!(to_strings(&mut trie.keys_with_prefix("rusty".chars())), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_longest_common_prefix() {
+        let mut trie = TrieTree::<u8>::new();
+        assert_eq!(trie.longest_common_prefix(), vec![]);
+        trie.insert(b"interview".iter().cloned());
+        assert_eq!(trie.longest_common_prefix(), b"interview");
+        trie.insert(b"internet".iter().cloned());
+        trie.insert(b"interval".iter().cloned());
+        assert_eq!(trie.longest_common_prefix(), b"inter");
+        trie.insert(b"in".iter().cloned());
+        assert_eq!(trie.longest_common_prefix(), b"in");
+        trie.remove(b"in".iter().cloned());
+        assert_eq!(trie.longest_common_prefix(), b"inter");
+    }
 }
@@ -1,5 +1,13 @@
-use num::{Integer, NumCast};
+use num::{Integer, Num, NumCast};
 
+use super::{
+    modulo::{crt, inverse_mod_mul, mod_mul_u64, mod_pow_u64_safe},
+    prime::factorize_u64,
+};
+use crate::polynomial::ntt::convolution_mod;
+
+/// factorials and their inverses mod prime p, rows of Stirling, Bell and Eulerian numbers are computed
+/// by NTT convolution of residues, so they require p < 2^32
 pub struct Counting<T: Integer> {
     p: T,
     fac: Vec<T>,
@@ -8,6 +16,8 @@ pub struct Counting<T: Integer> {
 impl<T: Integer + Copy + NumCast> Counting<T> {
     /// **O(n)**, ready to compute combination(n,k) mod p, where p is prime and larger than n
     pub fn new(max_n: usize, p: T) -> Self {
+        // i! for i >= p is zero, so the inverses would be wrong silently
+        assert!(T::from(max_n).is_some_and(|n| n < p), "p must be larger than max_n");
         let (mut fac, mut fac_inv, mut inv) =
             (vec![T::one(); max_n + 1], vec![T::one(); max_n + 1], vec![T::one(); max_n + 1]);
         for i in 2..=max_n {
@@ -20,6 +30,12 @@ impl<T: Integer + Copy + NumCast> Counting<T> {
         Counting { p, fac, fac_inv }
     }
 
+    /// **O(p)** time and memory, ready to compute combination_lucas(n,k) mod p for any n, where p is prime,
+    /// so p must be small enough to hold tables of p entries (p = 10^9 + 7 needs 10^9 entries, use new instead)
+    pub fn new_lucas(p: T) -> Self {
+        Self::new(p.to_usize().expect("p must be small") - 1, p)
+    }
+
     /// **O(1)**, compute n! mod p
     pub fn factorial(&self, n: usize) -> T {
         self.fac[n]
@@ -43,10 +59,329 @@ impl<T: Integer + Copy + NumCast> Counting<T> {
         }
     }
 
+    /// **O(log_p(n))**, compute nCk mod p by Lucas's theorem, factorial table must cover up to p - 1
+    pub fn combination_lucas(&self, mut n: u64, mut k: u64) -> T {
+        let p = self.p.to_u64().expect("p is positive");
+        assert!(self.fac.len() as u64 >= p, "factorial table must cover up to p - 1");
+        let mut result = T::one() % self.p;
+        while k > 0 {
+            let (ni, ki) = ((n % p) as usize, (k % p) as usize);
+            result = result * self.combination(ni, ki) % self.p;
+            n /= p;
+            k /= p;
+        }
+        result
+    }
+
     /// **O(1)**, compute nHk mod p
     pub fn combination_with_repetition(&self, n: usize, k: usize) -> T {
         self.combination(n + k - 1, k)
     }
+
+    /// **O(1)**, compute n-th Catalan number mod p, factorial table must cover up to 2n
+    pub fn catalan(&self, n: usize) -> T {
+        self.combination(2 * n, n) * (self.fac_inv[n + 1] * self.fac[n] % self.p) % self.p
+    }
+
+    /// **O(1)**, compute the number of sequences of n up and m down steps whose prefix never goes below zero
+    pub fn ballot(&self, n: usize, m: usize) -> T {
+        match (n < m, m) {
+            (true, _) => T::zero(),
+            (false, 0) => T::one() % self.p,
+            _ => (self.combination(n + m, m) + self.p - self.combination(n + m, m - 1)) % self.p,
+        }
+    }
+
+    fn to_u64(x: T) -> u64 {
+        x.to_u64().expect("residue fits in u64")
+    }
+
+    /// **O(1)**, p as u64 for NTT convolution of a row of n, after checking that the factorial table
+    /// covers up to n and p is less than 2^32
+    fn row_modulus(&self, n: usize) -> u64 {
+        assert!(n < self.fac.len(), "factorial table must cover up to {}", n);
+        let p = Self::to_u64(self.p);
+        assert!(p < 1 << 32, "p must be less than 2^32");
+        p
+    }
+
+    /// **O(n log(n))**, coefficients of f(x + c) from coefficients of f, p must be less than 2^32
+    fn taylor_shift(&self, f: &[u64], c: u64) -> Vec<u64> {
+        let (n, p) = (f.len(), Self::to_u64(self.p));
+        // f(x + c) = Σ_j x^j / j! Σ_i (f_i i!) (c^(i - j) / (i - j)!)
+        let a: Vec<_> =
+            (0..n).rev().map(|i| mod_mul_u64(f[i], Self::to_u64(self.fac[i]), p)).collect();
+        let mut power = 1 % p;
+        let b: Vec<_> = (0..n)
+            .map(|k| {
+                let term = mod_mul_u64(power, Self::to_u64(self.fac_inv[k]), p);
+                power = mod_mul_u64(power, c % p, p);
+                term
+            })
+            .collect();
+        let conv = convolution_mod(&a, &b, p);
+        (0..n).map(|j| mod_mul_u64(conv[n - 1 - j], Self::to_u64(self.fac_inv[j]), p)).collect()
+    }
+
+    /// **O(n log(n))**, coefficients of rising factorial x (x + 1) ... (x + n - 1) by doubling
+    fn rising_factorial(&self, n: usize) -> Vec<u64> {
+        let p = Self::to_u64(self.p);
+        if n == 0 {
+            return vec![1 % p];
+        }
+        let half = n / 2;
+        let lower = self.rising_factorial(half);
+        let upper = self.taylor_shift(&lower, half as u64);
+        let mut f = convolution_mod(&lower, &upper, p);
+        if n % 2 == 1 {
+            // multiply by x + n - 1
+            let c = (n as u64 - 1) % p;
+            f.push(0);
+            for i in (0..f.len()).rev() {
+                let shifted = if i > 0 { f[i - 1] } else { 0 };
+                f[i] = (mod_mul_u64(f[i], c, p) + shifted) % p;
+            }
+        }
+        f
+    }
+
+    /// **O(n log(n))**, unsigned Stirling numbers of the first kind c(n, k) for k in [0, n],
+    /// factorial table must cover up to n and p must be less than 2^32
+    pub fn stirling_first_row(&self, n: usize) -> Vec<T> {
+        self.row_modulus(n);
+        self.rising_factorial(n)
+            .into_iter()
+            .map(|x| T::from(x).expect("residue fits in T"))
+            .collect()
+    }
+
+    /// **O(n log(n))**, Stirling numbers of the second kind S(n, k) for k in [0, n],
+    /// factorial table must cover up to n and p must be less than 2^32
+    pub fn stirling_second_row(&self, n: usize) -> Vec<T> {
+        let p = self.row_modulus(n);
+        // S(n, k) = Σ_i i^n / i! * (-1)^(k - i) / (k - i)!
+        let a: Vec<_> = (0..=n)
+            .map(|i| {
+                mod_mul_u64(
+                    mod_pow_u64_safe(i as u64, n as u64, p),
+                    Self::to_u64(self.fac_inv[i]),
+                    p,
+                )
+            })
+            .collect();
+        let b: Vec<_> = (0..=n)
+            .map(|j| match Self::to_u64(self.fac_inv[j]) {
+                x if j % 2 == 1 => (p - x) % p,
+                x => x,
+            })
+            .collect();
+        convolution_mod(&a, &b, p)
+            .into_iter()
+            .take(n + 1)
+            .map(|x| T::from(x).expect("residue fits in T"))
+            .collect()
+    }
+
+    /// **O(n log(n))**, n-th Bell number mod p, factorial table must cover up to n and p must be less than 2^32
+    pub fn bell(&self, n: usize) -> T {
+        self.stirling_second_row(n).into_iter().fold(T::zero(), |acc, x| (acc + x) % self.p)
+    }
+
+    /// **O(n log(n))**, Eulerian numbers A(n, k) for k in [0, n], the number of permutations of n with k ascents,
+    /// factorial table must cover up to n + 1 and p must be less than 2^32
+    pub fn eulerian_row(&self, n: usize) -> Vec<T> {
+        if n == 0 {
+            return vec![T::one() % self.p];
+        }
+        let p = self.row_modulus(n + 1);
+        // A(n, k) = Σ_j (-1)^j C(n + 1, j) (k + 1 - j)^n
+        let a: Vec<_> = (0..=n)
+            .map(|j| match Self::to_u64(self.combination(n + 1, j)) {
+                x if j % 2 == 1 => (p - x) % p,
+                x => x,
+            })
+            .collect();
+        let b: Vec<_> = (0..=n).map(|i| mod_pow_u64_safe(i as u64 + 1, n as u64, p)).collect();
+        let mut row: Vec<_> = convolution_mod(&a, &b, p)
+            .into_iter()
+            .take(n)
+            .map(|x| T::from(x).expect("residue fits in T"))
+            .collect();
+        row.push(T::zero());
+        row
+    }
+}
+
+/// **O(n)**, 0, 1, ..., n as T, which needs no conversion from integers
+fn naturals<T: Num + Copy>(n: usize) -> Vec<T> {
+    std::iter::successors(Some(T::zero()), |&x| Some(x + T::one())).take(n + 1).collect()
+}
+
+/// **O(n^2)**, unsigned Stirling numbers of the first kind, table[n][k] is the number of permutations of n with k cycles
+pub fn stirling_first_table<T: Num + Copy>(n: usize) -> Vec<Vec<T>> {
+    let (mut table, naturals) = (vec![vec![T::zero(); n + 1]; n + 1], naturals::<T>(n));
+    table[0][0] = T::one();
+    for i in 1..=n {
+        for k in 1..=i {
+            table[i][k] = table[i - 1][k - 1] + naturals[i - 1] * table[i - 1][k];
+        }
+    }
+    table
+}
+
+/// **O(n^2)**, Stirling numbers of the second kind, table[n][k] is the number of partitions of n elements into k sets
+pub fn stirling_second_table<T: Num + Copy>(n: usize) -> Vec<Vec<T>> {
+    let (mut table, naturals) = (vec![vec![T::zero(); n + 1]; n + 1], naturals::<T>(n));
+    table[0][0] = T::one();
+    for i in 1..=n {
+        for k in 1..=i {
+            table[i][k] = table[i - 1][k - 1] + naturals[k] * table[i - 1][k];
+        }
+    }
+    table
+}
+
+/// **O(n^2)**, Bell numbers B_0, ..., B_n by Bell triangle
+pub fn bell_numbers<T: Num + Copy>(n: usize) -> Vec<T> {
+    let (mut bell, mut row) = (vec![T::one()], vec![T::one()]);
+    for _ in 0..n {
+        let mut next = vec![*row.last().expect("row is not empty")];
+        for &x in &row {
+            let last = *next.last().expect("next is not empty");
+            next.push(last + x);
+        }
+        bell.push(next[0]);
+        row = next;
+    }
+    bell
+}
+
+/// **O(n^2)**, Eulerian numbers, table[n][k] is the number of permutations of n with k ascents
+pub fn eulerian_table<T: Num + Copy>(n: usize) -> Vec<Vec<T>> {
+    let (mut table, naturals) = (vec![vec![T::zero(); n + 1]; n + 1], naturals::<T>(n));
+    table[0][0] = T::one();
+    for i in 1..=n {
+        for k in 0..i {
+            let stay = naturals[k + 1] * table[i - 1][k];
+            let rise = if k > 0 { naturals[i - k] * table[i - 1][k - 1] } else { T::zero() };
+            table[i][k] = stay + rise;
+        }
+    }
+    table
+}
+
+/// **O(n sqrt(n))**, partition numbers p(0), ..., p(n) by pentagonal number theorem
+pub fn partition_numbers<T: Num + Copy>(n: usize) -> Vec<T> {
+    let mut partitions = vec![T::one(); n + 1];
+    for i in 1..=n {
+        let mut sum = T::zero();
+        // p(i) = Σ_k (-1)^(k + 1) (p(i - k(3k - 1) / 2) + p(i - k(3k + 1) / 2))
+        for k in (1..).take_while(|k| k * (3 * k - 1) / 2 <= i) {
+            let mut term = partitions[i - k * (3 * k - 1) / 2];
+            if k * (3 * k + 1) / 2 <= i {
+                term = term + partitions[i - k * (3 * k + 1) / 2];
+            }
+            sum = if k % 2 == 1 { sum + term } else { sum - term };
+        }
+        partitions[i] = sum;
+    }
+    partitions
+}
+
+/// binomial coefficient modulo prime power p^e by Granville's generalization of Lucas's theorem
+pub struct BinomialPrimePower {
+    p: u64,
+    e: u32,
+    pe: u64,
+    /// unit_fac[i] is product of integers in [1, i] which are prime to p, mod p^e
+    unit_fac: Vec<u64>,
+}
+impl BinomialPrimePower {
+    /// **O(p^e)** time and memory, ready to compute combination(n,k) mod p^e, where p is prime,
+    /// so p^e must be small enough to hold a table of p^e entries
+    pub fn new(p: u64, e: u32) -> Self {
+        let pe = p.pow(e);
+        let mut unit_fac = vec![1 % pe; pe as usize + 1];
+        for i in 1..=pe as usize {
+            let factor = if (i as u64).is_multiple_of(p) { 1 } else { i as u64 };
+            unit_fac[i] = mod_mul_u64(unit_fac[i - 1], factor, pe);
+        }
+        BinomialPrimePower { p, e, pe, unit_fac }
+    }
+
+    /// **O(log_p(n) log(n))**, n! with all factor p removed, mod p^e
+    fn factorial_without_p(&self, mut n: u64) -> u64 {
+        let mut result = 1 % self.pe;
+        while n > 0 {
+            let blocks = mod_pow_u64_safe(self.unit_fac[self.pe as usize], n / self.pe, self.pe);
+            let rest = self.unit_fac[(n % self.pe) as usize];
+            result = mod_mul_u64(result, mod_mul_u64(blocks, rest, self.pe), self.pe);
+            n /= self.p;
+        }
+        result
+    }
+
+    /// **O(log_p(n))**, exponent of p in n! by Legendre's formula
+    fn legendre(&self, mut n: u64) -> u64 {
+        let mut count = 0;
+        while n > 0 {
+            n /= self.p;
+            count += n;
+        }
+        count
+    }
+
+    /// **O(log_p(n) log(n))**, compute nCk mod p^e
+    pub fn combination(&self, n: u64, k: u64) -> u64 {
+        if n < k {
+            return 0;
+        }
+        let exponent = self.legendre(n) - self.legendre(k) - self.legendre(n - k);
+        if exponent >= self.e as u64 || self.pe == 1 {
+            return 0;
+        }
+        let inv = |x: u64| inverse_mod_mul(x as i128, self.pe as i128).expect("prime to p") as u64;
+        let denominator =
+            mod_mul_u64(self.factorial_without_p(k), self.factorial_without_p(n - k), self.pe);
+        let unit = mod_mul_u64(self.factorial_without_p(n), inv(denominator), self.pe);
+        mod_mul_u64(unit, self.p.pow(exponent as u32), self.pe)
+    }
+}
+
+/// binomial coefficient modulo arbitrary m, combined from prime power moduli by CRT
+pub struct BinomialMod {
+    m: u64,
+    parts: Vec<BinomialPrimePower>,
+}
+impl BinomialMod {
+    /// **O(Σ p^e)** time and memory, ready to compute combination(n,k) mod m, where m = Π p^e,
+    /// large prime power factor is infeasible (m = 10^9 + 7 needs 10^9 entries, use Counting instead)
+    pub fn new(m: u64) -> Self {
+        assert!(m >= 1, "modulus must be positive");
+        let parts = match m {
+            1 => Vec::new(),
+            _ => factorize_u64(m)
+                .into_iter()
+                .map(|(p, e)| BinomialPrimePower::new(p, e as u32))
+                .collect(),
+        };
+        BinomialMod { m, parts }
+    }
+
+    /// **O(Σ log_p(n) log(n))**, compute nCk mod m
+    pub fn combination(&self, n: u64, k: u64) -> u64 {
+        let congruences: Vec<_> =
+            self.parts.iter().map(|part| (part.combination(n, k) as i64, part.pe as i64)).collect();
+        let (r, lcm) = crt(&congruences).expect("prime powers are coprime");
+        debug_assert_eq!(lcm as u64, self.m);
+        r as u64
+    }
+}
+
+/// **O(Σ p^e + log(n)^2)** time and **O(Σ p^e)** memory, compute nCk mod m for m whose prime power factors are small,
+/// use BinomialMod for many queries
+pub fn binomial_mod(n: u64, k: u64, m: u64) -> u64 {
+    BinomialMod::new(m).combination(n, k)
 }
 
 #[cfg(test)]
@@ -115,6 +450,12 @@ mod tests {
         assert_eq!(c1.combination(0, 1), 0);
     }
 
+    #[test]
+    #[should_panic(expected = "p must be larger than max_n")]
+    fn test_table_not_smaller_than_p() {
+        Counting::new(7, 7u64);
+    }
+
     #[test]
     fn test_combination_with_repetition() {
         let c = Counting::new(100, 1_000_000_007u128);
@@ -132,4 +473,157 @@ mod tests {
             }
         }
     }
+
+    fn pascal(n: usize, m: u64) -> Vec<Vec<u64>> {
+        let mut table = vec![vec![0; n + 1]; n + 1];
+        for i in 0..=n {
+            table[i][0] = 1 % m;
+            for j in 1..=i {
+                table[i][j] = (table[i - 1][j - 1] + table[i - 1][j]) % m;
+            }
+        }
+        table
+    }
+
+    #[test]
+    fn test_combination_lucas() {
+        for &p in &[2u64, 3, 5, 7, 13] {
+            let c = Counting::new_lucas(p);
+            let table = pascal(100, p);
+            for (n, row) in table.iter().enumerate() {
+                for (k, &expected) in row.iter().enumerate() {
+                    assert_eq!(c.combination_lucas(n as u64, k as u64), expected);
+                }
+            }
+        }
+        let c = Counting::new_lucas(10007u64);
+        assert_eq!(c.combination_lucas(10007, 1), 0);
+        assert_eq!(c.combination_lucas(10007 * 3 + 2, 10007 + 1), 3 * 2);
+        assert_eq!(c.combination_lucas(10007 * 10007, 10007), 0);
+        assert_eq!(c.combination_lucas(10007 * 10007 + 10007 * 4 + 5, 10007 * 3 + 2), 40);
+    }
+
+    #[test]
+    fn test_binomial_prime_power() {
+        for &(p, e) in &[(2, 1), (2, 5), (3, 3), (5, 2), (7, 1)] {
+            let c = BinomialPrimePower::new(p, e);
+            let table = pascal(120, p.pow(e));
+            for (n, row) in table.iter().enumerate() {
+                for (k, &expected) in row.iter().enumerate() {
+                    assert_eq!(c.combination(n as u64, k as u64), expected, "{}^{}", p, e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_binomial_mod() {
+        for &m in &[1, 2, 6, 10, 12, 100, 360, 1001] {
+            let c = BinomialMod::new(m);
+            let table = pascal(80, m);
+            for (n, row) in table.iter().enumerate() {
+                for (k, &expected) in row.iter().enumerate() {
+                    assert_eq!(c.combination(n as u64, k as u64), expected, "mod {}", m);
+                }
+            }
+        }
+        // C(10^18, 10^9) mod 10^6 agrees with its residues mod 2^6 and 5^6
+        let (n, k) = (1_000_000_000_000_000_000, 1_000_000_000);
+        let r = binomial_mod(n, k, 1_000_000);
+        assert_eq!(r % 64, BinomialPrimePower::new(2, 6).combination(n, k));
+        assert_eq!(r % 15625, BinomialPrimePower::new(5, 6).combination(n, k));
+        let c = Counting::new_lucas(10007u64);
+        assert_eq!(
+            binomial_mod(123_456_789, 98765, 10007),
+            c.combination_lucas(123_456_789, 98765)
+        );
+        assert_eq!(binomial_mod(10, 3, 1), 0);
+    }
+
+    #[test]
+    fn test_catalan_ballot() {
+        let c = Counting::new(100, 1_000_000_007u64);
+        let catalan: Vec<_> = (0..8).map(|n| c.catalan(n)).collect();
+        assert_eq!(catalan, [1, 1, 2, 5, 14, 42, 132, 429]);
+        assert_eq!(c.ballot(3, 3), 5);
+        assert_eq!(c.ballot(3, 1), 3);
+        assert_eq!(c.ballot(4, 0), 1);
+        assert_eq!(c.ballot(1, 2), 0);
+        for n in 0..20 {
+            assert_eq!(c.ballot(n, n), c.catalan(n));
+        }
+    }
+
+    #[test]
+    fn test_stirling() {
+        use crate::integer::static_modint::ModInt998244353 as Mint;
+        let first = stirling_first_table::<Mint>(60);
+        let second = stirling_second_table::<Mint>(60);
+        assert_eq!(first[4][..5], [0, 6, 11, 6, 1].map(Mint::new));
+        assert_eq!(second[4][..5], [0, 1, 7, 6, 1].map(Mint::new));
+        let c = Counting::new(100, 998_244_353u64);
+        for n in 0..=60 {
+            let expected_first: Vec<_> = first[n][..=n].iter().map(|x| x.value()).collect();
+            let expected_second: Vec<_> = second[n][..=n].iter().map(|x| x.value()).collect();
+            assert_eq!(c.stirling_first_row(n), expected_first);
+            assert_eq!(c.stirling_second_row(n), expected_second);
+        }
+        let c = Counting::new(100, 1_000_000_007u64);
+        assert_eq!(c.stirling_first_row(3), [0, 2, 3, 1]);
+        assert_eq!(c.stirling_second_row(0), [1]);
+    }
+
+    #[test]
+    fn test_bell() {
+        use crate::integer::static_modint::ModInt1000000007 as Mint;
+        let bell = bell_numbers::<Mint>(30);
+        let expected = [1, 1, 2, 5, 15, 52, 203, 877, 4140, 21147];
+        assert_eq!(bell[..10], expected.map(Mint::new));
+        let c = Counting::new(100, 1_000_000_007u64);
+        for (n, b) in bell.iter().enumerate() {
+            assert_eq!(c.bell(n), b.value());
+        }
+    }
+
+    #[test]
+    fn test_eulerian() {
+        use crate::integer::static_modint::ModInt998244353 as Mint;
+        let table = eulerian_table::<Mint>(40);
+        assert_eq!(table[4][..5], [1, 11, 11, 1, 0].map(Mint::new));
+        let c = Counting::new(100, 998_244_353u64);
+        let mut factorial = Mint::new(1);
+        for (n, row) in table.iter().enumerate() {
+            let expected: Vec<_> = row.iter().take(n + 1).map(|x| x.value()).collect();
+            assert_eq!(c.eulerian_row(n), expected);
+            if n > 0 {
+                factorial *= Mint::from(n);
+            }
+            assert_eq!(row.iter().sum::<Mint>(), factorial);
+        }
+    }
+
+    #[test]
+    fn test_partition_numbers() {
+        let partitions = partition_numbers::<u64>(100);
+        assert_eq!(partitions[..11], [1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42]);
+        assert_eq!(partitions[100], 190_569_292);
+        use crate::integer::dynamic_modint::DynamicModInt;
+        let partitions = partition_numbers::<DynamicModInt>(1000);
+        assert_eq!(
+            partitions[1000].value(),
+            (24_061_467_864_032_622_473_692_149_727_991u128 % 1_000_000_007) as u64
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "factorial table must cover up to 11")]
+    fn test_row_beyond_table() {
+        Counting::new(10, 998_244_353u64).stirling_second_row(11);
+    }
+
+    #[test]
+    #[should_panic(expected = "p must be less than 2^32")]
+    fn test_row_large_modulus() {
+        Counting::new(10, 9_007_199_254_740_997u128).bell(5);
+    }
 }
@@ -1,4 +1,7 @@
 use num::{range_inclusive, Integer, ToPrimitive, Unsigned};
+use std::collections::HashMap;
+
+use super::prime::factorize_u64;
 
 /// **O(sqrt(n))**, calculate vec of pair os devisors
 pub fn devisors_pair<T: Integer + ToPrimitive + Unsigned + Copy>(n: T) -> Vec<(T, T)> {
@@ -17,6 +20,31 @@ pub fn devisors<T: Integer + ToPrimitive + Unsigned + Copy>(n: T) -> Vec<T> {
     devisors
 }
 
+/// **O(d(n))**, calculate sorted vec of devisors from prime factorization, factors less than 2 are ignored
+pub fn devisors_from_factorization(facts: &HashMap<u64, usize>) -> Vec<u64> {
+    let mut devisors = vec![1];
+    for (&p, &e) in facts.iter().filter(|&(&p, _)| p > 1) {
+        let len = devisors.len();
+        let mut power = 1;
+        for _ in 0..e {
+            power *= p;
+            for i in 0..len {
+                devisors.push(devisors[i] * power);
+            }
+        }
+    }
+    devisors.sort_unstable();
+    devisors
+}
+
+/// **O(n^(1/4) log(n) + d(n) log(d(n)))** expected, calculate vec of devisors by Pollard's rho
+pub fn devisors_u64(n: u64) -> Vec<u64> {
+    match n {
+        0 => Vec::new(),
+        _ => devisors_from_factorization(&factorize_u64(n)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +100,16 @@ mod tests {
         assert_eq!(devisors(1u64), [1]);
         assert_eq!(devisors(2u128), [1, 2]);
     }
+
+    #[test]
+    fn test_devisors_u64() {
+        for n in 0..1000u64 {
+            assert_eq!(devisors_u64(n), devisors(n));
+        }
+        let n = 1_000_000_007 * 998_244_353;
+        assert_eq!(devisors_u64(n), [1, 998_244_353, 1_000_000_007, n]);
+        assert_eq!(devisors_u64(735_134_400).len(), 1344);
+        let facts = vec![(2, 2), (3, 1)].into_iter().collect();
+        assert_eq!(devisors_from_factorization(&facts), [1, 2, 3, 4, 6, 12]);
+    }
 }
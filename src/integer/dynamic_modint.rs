@@ -0,0 +1,371 @@
+use num::{Num, One, Zero};
+use std::{
+    cell::Cell,
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::{modulo::inverse_mod_mul, static_modint::ParseModIntError};
+
+/// Barrett reduction for modulus in [1, 2^63], which replace division by multiplication
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Barrett {
+    modulo: u64,
+    inv: u128, // floor((2^128 - 1) / modulo)
+}
+impl Barrett {
+    /// **O(1)**, prepare reduction of mod modulo
+    pub fn new(modulo: u64) -> Self {
+        assert!((1..=1 << 63).contains(&modulo), "modulus must be in [1, 2^63]");
+        Barrett { modulo, inv: u128::MAX / modulo as u128 }
+    }
+
+    /// **O(1)**, the modulus
+    pub fn modulo(&self) -> u64 {
+        self.modulo
+    }
+
+    /// **O(1)**, calculate x mod modulo for x < 2^126
+    pub fn reduce(&self, x: u128) -> u64 {
+        // quotient is underestimated at most by 2
+        let q = mul_high(x, self.inv);
+        let mut r = x - q * self.modulo as u128;
+        while r >= self.modulo as u128 {
+            r -= self.modulo as u128;
+        }
+        r as u64
+    }
+
+    /// **O(1)**, calculate a * b mod modulo for a, b < modulo
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    /// **O(log(e))**, calculate a^e mod modulo for a < modulo
+    pub fn pow(&self, mut a: u64, mut e: u64) -> u64 {
+        let mut result = self.reduce(1);
+        while e > 0 {
+            if e & 1 == 1 {
+                result = self.mul(result, a);
+            }
+            a = self.mul(a, a);
+            e >>= 1;
+        }
+        result
+    }
+}
+
+/// **O(1)**, upper 128 bits of a * b
+fn mul_high(a: u128, b: u128) -> u128 {
+    let (a_lo, a_hi) = (a as u64 as u128, a >> 64);
+    let (b_lo, b_hi) = (b as u64 as u128, b >> 64);
+    let (lo_lo, lo_hi, hi_lo) = (a_lo * b_lo, a_lo * b_hi, a_hi * b_lo);
+    let middle = (lo_lo >> 64) + (lo_hi as u64 as u128) + (hi_lo as u64 as u128);
+    a_hi * b_hi + (lo_hi >> 64) + (hi_lo >> 64) + (middle >> 64)
+}
+
+thread_local! {
+    static BARRETT: Cell<Barrett> = Cell::new(Barrett::new(1_000_000_007));
+}
+
+/// integer modulo runtime modulus shared in the thread (default is 1e9+7),
+/// values made before changing the modulus are invalid
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DynamicModInt {
+    value: u64,
+}
+impl DynamicModInt {
+    /// **O(1)**, set the modulus in [1, 2^63] of this thread
+    pub fn set_modulus(modulo: u64) {
+        BARRETT.with(|barrett| barrett.set(Barrett::new(modulo)));
+    }
+
+    /// **O(1)**, the modulus of this thread
+    pub fn modulus() -> u64 {
+        Self::barrett().modulo
+    }
+
+    fn barrett() -> Barrett {
+        BARRETT.with(|barrett| barrett.get())
+    }
+
+    /// **O(1)**, make value % modulus
+    pub fn new(value: u64) -> Self {
+        DynamicModInt { value: Self::barrett().reduce(value as u128) }
+    }
+
+    /// **O(1)**, representative in [0, modulus)
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// **O(log(e))**, calculate self^e
+    pub fn pow(&self, e: u64) -> Self {
+        DynamicModInt { value: Self::barrett().pow(self.value, e) }
+    }
+
+    /// **O(log(modulus))**, multiplicative inverse if self and modulus are coprime, else None
+    pub fn checked_inv(&self) -> Option<Self> {
+        inverse_mod_mul(self.value as i128, Self::modulus() as i128)
+            .map(|inv| DynamicModInt { value: inv as u64 })
+    }
+
+    /// **O(log(modulus))**, multiplicative inverse (panic if self and modulus are not coprime)
+    pub fn inv(&self) -> Self {
+        self.checked_inv().expect("value must be coprime to modulus")
+    }
+}
+
+impl fmt::Display for DynamicModInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+impl fmt::Debug for DynamicModInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value, Self::modulus())
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for DynamicModInt {
+            fn from(x: $t) -> Self {
+                DynamicModInt { value: (x as u128 % Self::modulus() as u128) as u64 }
+            }
+        }
+    )*};
+}
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for DynamicModInt {
+            /// negative value is converted into [0, modulus) as well
+            fn from(x: $t) -> Self {
+                DynamicModInt { value: (x as i128).rem_euclid(Self::modulus() as i128) as u64 }
+            }
+        }
+    )*};
+}
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Add for DynamicModInt {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        // modulus is at most 2^63, so the sum does not overflow
+        let (value, modulo) = (self.value + rhs.value, Self::modulus());
+        DynamicModInt { value: if value >= modulo { value - modulo } else { value } }
+    }
+}
+impl Sub for DynamicModInt {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let (value, modulo) = (self.value + Self::modulus() - rhs.value, Self::modulus());
+        DynamicModInt { value: if value >= modulo { value - modulo } else { value } }
+    }
+}
+impl Mul for DynamicModInt {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        DynamicModInt { value: Self::barrett().mul(self.value, rhs.value) }
+    }
+}
+impl Div for DynamicModInt {
+    type Output = Self;
+    /// panic if rhs is not invertible
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+impl Rem for DynamicModInt {
+    type Output = Self;
+    /// representatives are divided as integers, the modulus set at runtime may not be prime
+    fn rem(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "remainder by zero");
+        DynamicModInt { value: self.value % rhs.value }
+    }
+}
+impl Neg for DynamicModInt {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+impl AddAssign for DynamicModInt {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl SubAssign for DynamicModInt {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+impl MulAssign for DynamicModInt {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+impl DivAssign for DynamicModInt {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for DynamicModInt {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |sum, x| sum + x)
+    }
+}
+impl<'a> Sum<&'a Self> for DynamicModInt {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+impl Product for DynamicModInt {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |product, x| product * x)
+    }
+}
+impl<'a> Product<&'a Self> for DynamicModInt {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().product()
+    }
+}
+
+impl Zero for DynamicModInt {
+    fn zero() -> Self {
+        DynamicModInt { value: 0 }
+    }
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+impl One for DynamicModInt {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Num for DynamicModInt {
+    type FromStrRadixErr = ParseModIntError;
+    /// **O(|s|)**, parse integer of any length, leading '-' is allowed
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseModIntError> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() {
+            return Err(ParseModIntError);
+        }
+        let radix_mod = Self::from(radix);
+        let value = digits.chars().try_fold(Self::zero(), |value, c| {
+            c.to_digit(radix).map(|d| value * radix_mod + Self::from(d)).ok_or(ParseModIntError)
+        })?;
+        Ok(if negative { -value } else { value })
+    }
+}
+impl FromStr for DynamicModInt {
+    type Err = ParseModIntError;
+    fn from_str(s: &str) -> Result<Self, ParseModIntError> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::modulo::mod_pow_u64;
+    use rand::RngExt;
+
+    #[test]
+    fn test_barrett() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let modulo = rng.random_range(1..=1u64 << 63);
+            let barrett = Barrett::new(modulo);
+            let (a, b) = (rng.random_range(0..modulo), rng.random_range(0..modulo));
+            assert_eq!(barrett.mul(a, b) as u128, a as u128 * b as u128 % modulo as u128);
+            let x = rng.random_range(0..1u128 << 126);
+            assert_eq!(barrett.reduce(x) as u128, x % modulo as u128);
+        }
+        let barrett = Barrett::new(1 << 63);
+        assert_eq!(barrett.mul((1 << 63) - 1, (1 << 63) - 1), 1);
+        assert_eq!(Barrett::new(1).pow(5, 0), 0);
+    }
+
+    #[test]
+    fn test_pow_random() {
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            // mod_pow_u64 is correct only if modulo^2 fit in u64
+            let modulo = rng.random_range(1..1u64 << 32);
+            let (a, e) = (rng.random_range(0..modulo), rng.random_range(0..1u64 << 40));
+            assert_eq!(Barrett::new(modulo).pow(a, e), mod_pow_u64(a, e, modulo));
+        }
+        for _ in 0..1000 {
+            let modulo = rng.random_range(1 << 32..=1u64 << 63);
+            let (a, e) = (rng.random_range(0..modulo), rng.random_range(0..1000));
+            let naive = (0..e).fold(1 % modulo as u128, |p, _| p * a as u128 % modulo as u128);
+            assert_eq!(Barrett::new(modulo).pow(a, e) as u128, naive);
+        }
+    }
+
+    #[test]
+    fn test_dynamic_modint() {
+        DynamicModInt::set_modulus(998_244_353);
+        let (a, b) = (DynamicModInt::new(998_244_352), DynamicModInt::from(-2));
+        assert_eq!(a + b, DynamicModInt::new(998_244_350));
+        assert_eq!(a - b, DynamicModInt::one());
+        assert_eq!(a * b, DynamicModInt::new(2));
+        assert_eq!(a / b * b, a);
+        assert_eq!(-a, DynamicModInt::one());
+        assert_eq!(DynamicModInt::new(3).pow(998_244_352), DynamicModInt::one());
+        assert_eq!("-3".parse::<DynamicModInt>(), Ok(DynamicModInt::new(998_244_350)));
+        assert_eq!(
+            (1..=10u64).map(DynamicModInt::new).product::<DynamicModInt>().value(),
+            3_628_800
+        );
+
+        // large prime modulus
+        let p = (1u64 << 61) - 1;
+        DynamicModInt::set_modulus(p);
+        let x = DynamicModInt::new(1 << 60);
+        assert_eq!((x * x).value(), 1 << 59);
+        assert_eq!(DynamicModInt::new(2).pow(61), DynamicModInt::one());
+        assert_eq!(x * x.inv(), DynamicModInt::one());
+
+        // composite modulus
+        DynamicModInt::set_modulus(1 << 62);
+        assert_eq!(DynamicModInt::new(2).checked_inv(), None);
+        assert_eq!(DynamicModInt::new(3) * DynamicModInt::new(3).inv(), DynamicModInt::one());
+        assert_eq!(DynamicModInt::new(1 << 31).pow(2), DynamicModInt::zero());
+        assert_eq!(DynamicModInt::new(100) % DynamicModInt::new(7), DynamicModInt::new(2));
+        DynamicModInt::set_modulus(1_000_000_007);
+    }
+
+    #[test]
+    fn test_num_interop() {
+        use crate::cumsum::cumsum1d::CumSum;
+        use crate::integer::counting::Counting;
+        DynamicModInt::set_modulus(1_000_000_007);
+        let counting = Counting::new(60, 1_000_000_007u64);
+        let factorial = |n| DynamicModInt::new(counting.factorial(n));
+        let cumsum: CumSum<_> = (0..=60).map(factorial).collect();
+        assert_eq!(cumsum.interval_sum(10..40), (10..40).map(factorial).sum());
+        // euclidean algorithm written for Num works on the representatives
+        fn gcd<T: Num + Copy>(a: T, b: T) -> T {
+            if b.is_zero() {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+        DynamicModInt::set_modulus(12);
+        assert_eq!(DynamicModInt::new(11) % DynamicModInt::new(4), DynamicModInt::new(3));
+        assert_eq!(gcd(DynamicModInt::new(8), DynamicModInt::new(6)), DynamicModInt::new(2));
+    }
+}
@@ -0,0 +1,189 @@
+use num::Num;
+
+/// linear sieve, tables of multiplicative functions are indexed by integer, so their size is n+1
+pub struct LinearSieve {
+    pub min_primes: Vec<usize>,
+    pub primes: Vec<usize>,
+    pub mobius: Vec<i8>,
+    pub euler_phi: Vec<usize>,
+    pub divisor_count: Vec<usize>,
+    pub divisor_sum: Vec<u64>,
+    /// p^e such that p = min_primes[i] and p^e is the largest power of p dividing i
+    min_prime_powers: Vec<usize>,
+    /// e of min_prime_powers
+    exponents: Vec<u32>,
+}
+impl LinearSieve {
+    /// **O(n)**, calculate minimum prime, μ, φ, d and σ of 0 to n, each integer is sieved only once by its minimum prime
+    pub fn new(n: usize) -> Self {
+        let size = n + 1;
+        let mut min_primes = vec![0; size];
+        let (mut mobius, mut euler_phi) = (vec![0; size], vec![0; size]);
+        let (mut divisor_count, mut divisor_sum) = (vec![0; size], vec![0; size]);
+        let (mut min_prime_powers, mut exponents) = (vec![1; size], vec![0; size]);
+        let mut primes = Vec::new();
+        if n >= 1 {
+            min_primes[1] = 1;
+            mobius[1] = 1;
+            euler_phi[1] = 1;
+            divisor_count[1] = 1;
+            divisor_sum[1] = 1;
+        }
+        for i in 2..size {
+            if min_primes[i] == 0 {
+                min_primes[i] = i;
+                primes.push(i);
+                mobius[i] = -1;
+                euler_phi[i] = i - 1;
+                divisor_count[i] = 2;
+                divisor_sum[i] = i as u64 + 1;
+                min_prime_powers[i] = i;
+                exponents[i] = 1;
+            }
+            for &p in &primes {
+                if p > min_primes[i] || i * p >= size {
+                    break;
+                }
+                let j = i * p;
+                min_primes[j] = p;
+                if p == min_primes[i] {
+                    // i and j share the minimum prime, so only the power of p grows
+                    let rest = i / min_prime_powers[i];
+                    min_prime_powers[j] = min_prime_powers[i] * p;
+                    exponents[j] = exponents[i] + 1;
+                    euler_phi[j] = euler_phi[i] * p;
+                    divisor_count[j] = divisor_count[rest] * (exponents[j] as usize + 1);
+                    // σ(p^(e+1)) = 1 + p σ(p^e)
+                    let sum_of_power = divisor_sum[min_prime_powers[i]] * p as u64 + 1;
+                    divisor_sum[j] = divisor_sum[rest] * sum_of_power;
+                } else {
+                    min_prime_powers[j] = p;
+                    exponents[j] = 1;
+                    mobius[j] = -mobius[i];
+                    euler_phi[j] = euler_phi[i] * (p - 1);
+                    divisor_count[j] = divisor_count[i] * 2;
+                    divisor_sum[j] = divisor_sum[i] * (p as u64 + 1);
+                }
+            }
+        }
+        LinearSieve {
+            min_primes,
+            primes,
+            mobius,
+            euler_phi,
+            divisor_count,
+            divisor_sum,
+            min_prime_powers,
+            exponents,
+        }
+    }
+
+    /// **O(1)**, if num is prime then return true, else return false
+    pub fn is_prime(&self, num: usize) -> bool {
+        num > 1 && num == self.min_primes[num]
+    }
+
+    /// **O(log(n))**, calculate prime factorization of n as sorted vec of (prime, exponent)
+    pub fn factorization(&self, mut n: usize) -> Vec<(usize, u32)> {
+        let mut facts = Vec::new();
+        while n > 1 {
+            facts.push((self.min_primes[n], self.exponents[n]));
+            n /= self.min_prime_powers[n];
+        }
+        facts
+    }
+
+    /// **O(n)**, table of multiplicative function f from 0 to n, where f(p^e) = f_prime_power(p, e),
+    /// f(0) is zero and f(1) is one
+    pub fn multiplicative<T, F>(&self, f_prime_power: F) -> Vec<T>
+    where
+        T: Num + Copy,
+        F: Fn(usize, u32) -> T,
+    {
+        let size = self.min_primes.len();
+        let mut table = vec![T::zero(); size];
+        if size > 1 {
+            table[1] = T::one();
+        }
+        for i in 2..size {
+            let (power, rest) = (self.min_prime_powers[i], i / self.min_prime_powers[i]);
+            table[i] = match rest {
+                1 => f_prime_power(self.min_primes[i], self.exponents[i]),
+                _ => table[power] * table[rest],
+            };
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::{devisors::devisors, prime::factorization};
+
+    #[test]
+    fn test_small() {
+        let sieve = LinearSieve::new(12);
+        assert_eq!(sieve.min_primes, [0, 1, 2, 3, 2, 5, 2, 7, 2, 3, 2, 11, 2]);
+        assert_eq!(sieve.primes, [2, 3, 5, 7, 11]);
+        assert_eq!(sieve.mobius, [0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]);
+        assert_eq!(sieve.euler_phi, [0, 1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+        assert_eq!(sieve.divisor_count, [0, 1, 2, 2, 3, 2, 4, 2, 4, 3, 4, 2, 6]);
+        assert_eq!(sieve.divisor_sum, [0, 1, 3, 4, 7, 6, 12, 8, 15, 13, 18, 12, 28]);
+        assert_eq!(sieve.factorization(12), [(2, 2), (3, 1)]);
+        assert_eq!(sieve.factorization(1), []);
+        assert!(sieve.is_prime(11));
+        assert!(!sieve.is_prime(1));
+    }
+
+    #[test]
+    fn test_bound() {
+        let sieve = LinearSieve::new(0);
+        assert_eq!(sieve.min_primes, [0]);
+        assert_eq!(sieve.multiplicative(|_, _| 1u64), [0]);
+        let sieve = LinearSieve::new(1);
+        assert_eq!(sieve.primes, []);
+        assert_eq!(sieve.mobius, [0, 1]);
+        assert_eq!(sieve.multiplicative(|_, _| 1u64), [0, 1]);
+    }
+
+    #[test]
+    fn test_naive() {
+        let n = 3000;
+        let sieve = LinearSieve::new(n);
+        for i in 1..=n {
+            let facts = factorization(i as u64);
+            let squarefree = facts.values().all(|&e| e == 1);
+            let mobius = match (i, squarefree) {
+                (1, _) => 1,
+                (_, true) => (-1i8).pow(facts.len() as u32),
+                (_, false) => 0,
+            };
+            assert_eq!(sieve.mobius[i], mobius);
+            let ds = devisors(i as u64);
+            assert_eq!(sieve.divisor_count[i], ds.len());
+            assert_eq!(sieve.divisor_sum[i], ds.iter().sum::<u64>());
+            let phi = (1..=i).filter(|&j| num::integer::gcd(i, j) == 1).count();
+            assert_eq!(sieve.euler_phi[i], phi);
+            assert_eq!(sieve.is_prime(i), i > 1 && ds.len() == 2);
+        }
+    }
+
+    #[test]
+    fn test_multiplicative() {
+        let sieve = LinearSieve::new(1000);
+        let phi = sieve.multiplicative(|p, e| p.pow(e - 1) * (p - 1));
+        assert_eq!(phi, sieve.euler_phi);
+        let mobius = sieve.multiplicative(|_, e| if e == 1 { -1i64 } else { 0 });
+        assert!(mobius.iter().zip(&sieve.mobius).all(|(&x, &y)| x == y as i64));
+        // σ_2(n), sum of squares of divisors
+        let sigma2 =
+            sieve.multiplicative(|p, e| (0..=e).map(|k| (p as u64).pow(2 * k)).sum::<u64>());
+        assert_eq!(sigma2[12], 1 + 4 + 9 + 16 + 36 + 144);
+        // number of ways to write n as a product of two coprime integers in order, 2^ω(n)
+        use crate::integer::static_modint::ModInt998244353 as Mint;
+        let unitary = sieve.multiplicative(|_, _| Mint::new(2));
+        assert_eq!(unitary[30], Mint::new(8));
+        assert_eq!(unitary[1], Mint::new(1));
+    }
+}
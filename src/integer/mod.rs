@@ -1,10 +1,13 @@
 pub mod counting;
 pub mod devisors;
+pub mod dynamic_modint;
 pub mod gcd;
 pub mod interval_sieve;
+pub mod linear_sieve;
 pub mod modint;
 pub mod modulo;
 pub mod prime;
 pub mod rational;
 pub mod sieve;
 pub mod sort;
+pub mod static_modint;
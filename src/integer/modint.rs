@@ -1,8 +1,8 @@
 use num::Integer;
 
+/// integer modulo runtime modulus, use static_modint::StaticModInt if the modulus is constant
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct ModInt<T: Integer + Copy> {
-    // TODO: to represent modulo, use type semantics
     int: T,
     modulo: T,
 }
@@ -1,4 +1,7 @@
 use num::{Integer, Signed};
+use std::collections::HashMap;
+
+use super::prime::factorize_u64;
 
 /// **O(log(b))**, calculate a^b % modulo
 pub fn mod_pow<T: Integer + Copy>(a: T, b: T, modulo: T) -> T {
@@ -17,7 +20,7 @@ pub fn mod_pow<T: Integer + Copy>(a: T, b: T, modulo: T) -> T {
 }
 
 /// **O(log(b))**, calculate a^b % modulo
-pub fn mod_pow_u64(a: u64, b: u64, modulo: u64) -> u64 {
+pub const fn mod_pow_u64(a: u64, b: u64, modulo: u64) -> u64 {
     if b == 0 {
         1
     } else {
@@ -30,6 +33,24 @@ pub fn mod_pow_u64(a: u64, b: u64, modulo: u64) -> u64 {
     }
 }
 
+/// **O(1)**, calculate a * b % modulo with u128 intermediate, which never overflows
+pub const fn mod_mul_u64(a: u64, b: u64, modulo: u64) -> u64 {
+    (a as u128 * b as u128 % modulo as u128) as u64
+}
+
+/// **O(log(b))**, calculate a^b % modulo with u128 intermediate, which works for any modulo
+pub const fn mod_pow_u64_safe(a: u64, mut b: u64, modulo: u64) -> u64 {
+    let (mut result, mut base) = (1 % modulo, a % modulo);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = mod_mul_u64(result, base, modulo);
+        }
+        base = mod_mul_u64(base, base, modulo);
+        b >>= 1;
+    }
+    result
+}
+
 /// **O(log(min(a, b)))**, calculate pair (gcd(a,b), x, y) such that ax + by = gcd(a, b)
 pub fn ex_euclid<T: Integer + Signed + Copy>(a: T, b: T) -> ((T, T), T) {
     if a == T::zero() {
@@ -53,6 +74,263 @@ pub fn inverse_mod_mul<T: Integer + Signed + Copy>(a: T, modulo: T) -> Option<T>
     }
 }
 
+/// **O(n log(max(m)))**, solve x ≡ r_i (mod m_i) for all (r_i, m_i), moduli need not be coprime,
+/// return (x, lcm) such that 0 <= x < lcm, None if there is no solution or lcm overflows i64
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut r, mut m) = (0i128, 1i128);
+    for &(ri, mi) in congruences {
+        assert!(mi >= 1, "modulus must be positive");
+        let (ri, mi) = ((ri as i128).rem_euclid(mi as i128), mi as i128);
+        let ((x, _), g) = ex_euclid(m, mi);
+        if (ri - r) % g != 0 {
+            return None;
+        }
+        // m * x ≡ g (mod mi), so r + m * ((ri - r) / g * x) satisfies both
+        let step = mi / g;
+        let t = ((ri - r) / g % step * (x % step)).rem_euclid(step);
+        r += m * t;
+        m *= step;
+        if m > i64::MAX as i128 {
+            return None;
+        }
+    }
+    Some((r as i64, m as i64))
+}
+
+/// **O(n^2)**, Garner's algorithm, calculate x % modulo where x is unique solution in [0, Π m_i)
+/// of x ≡ r_i (mod m_i) for all (r_i, m_i), None if moduli are not pairwise coprime
+pub fn garner(congruences: &[(u64, u64)], modulo: u64) -> Option<u64> {
+    let n = congruences.len();
+    let moduli: Vec<_> = congruences.iter().map(|&(_, m)| m).chain(Some(modulo)).collect();
+    // x = Σ t_i Π_{j < i} m_j, coeffs[k] = Π_{j < i} m_j and consts[k] = Σ_{j < i} t_j Π_{l < j} m_l (mod moduli[k])
+    let (mut coeffs, mut consts) = (vec![1; n + 1], vec![0; n + 1]);
+    for k in 0..=n {
+        coeffs[k] %= moduli[k];
+    }
+    for (i, &(r, m)) in congruences.iter().enumerate() {
+        let t = if m == 1 {
+            0
+        } else {
+            let inv = inverse_mod_mul(coeffs[i] as i128, m as i128)? as u64;
+            mod_mul_u64((r % m + m - consts[i]) % m, inv, m)
+        };
+        for k in i + 1..=n {
+            let added = consts[k] as u128 + mod_mul_u64(t, coeffs[k], moduli[k]) as u128;
+            consts[k] = (added % moduli[k] as u128) as u64;
+            coeffs[k] = mod_mul_u64(coeffs[k], m, moduli[k]);
+        }
+    }
+    Some(consts[n])
+}
+
+/// **O(sqrt(m))**, baby-step giant-step, the smallest x >= 0 such that a^x ≡ b (mod m), m need not be prime
+pub fn discrete_log(a: u64, b: u64, m: u64) -> Option<u64> {
+    let (mut a, mut b, mut m) = (a % m, b % m, m);
+    let mut power = 1 % m;
+    for x in 0..64 {
+        if power == b {
+            return Some(x);
+        }
+        power = mod_mul_u64(power, a, m);
+    }
+    // strip common factor of a and m, then a^x = coeff * a^(x - shift) with a prime to m
+    let (mut shift, mut coeff) = (0, 1 % m);
+    loop {
+        let g = a.gcd(&m);
+        if g == 1 {
+            break;
+        }
+        if !b.is_multiple_of(g) {
+            return None;
+        }
+        b /= g;
+        m /= g;
+        shift += 1;
+        coeff = mod_mul_u64(coeff % m, a / g % m, m);
+        a %= m;
+        b %= m;
+    }
+    let step = (m as f64).sqrt().ceil() as u64 + 1;
+    // baby steps b * a^j, keep the largest j for the smallest answer
+    let mut baby = HashMap::new();
+    let mut current = b;
+    for j in 0..step {
+        baby.insert(current, j);
+        current = mod_mul_u64(current, a, m);
+    }
+    let giant = mod_pow_u64_safe(a, step, m);
+    let mut current = coeff;
+    for i in 1..=step {
+        current = mod_mul_u64(current, giant, m);
+        if let Some(&j) = baby.get(&current) {
+            return Some(i * step - j + shift);
+        }
+    }
+    None
+}
+
+/// **O(√p log(p))**, the smallest primitive root of prime p < 2^32,
+/// const so that the root of a modulus given as const generic is fixed at compile time
+pub const fn primitive_root(p: u64) -> u64 {
+    // p - 1 < 2^32 has at most 9 distinct prime factors
+    let (mut factors, mut count, mut rest, mut d) = ([0; 16], 0, p - 1, 2);
+    while d * d <= rest {
+        if rest % d == 0 {
+            factors[count] = d;
+            count += 1;
+            while rest % d == 0 {
+                rest /= d;
+            }
+        }
+        d += 1;
+    }
+    if rest > 1 {
+        factors[count] = rest;
+        count += 1;
+    }
+    smallest_generator(p, factors.split_at(count).0)
+}
+
+/// **O(p^(1/4) log(p) + log(p)^2)** expected, the smallest primitive root of any prime p < 2^64,
+/// p - 1 is factorized by Pollard's rho instead of trial division
+pub fn primitive_root_u64(p: u64) -> u64 {
+    let factors: Vec<_> = factorize_u64(p - 1).into_keys().collect();
+    smallest_generator(p, &factors)
+}
+
+/// **O(g |factors| log(p))**, the smallest g such that g^((p - 1) / f) != 1 for all distinct prime factors f of p - 1,
+/// 1 if there is no such g (p = 2)
+const fn smallest_generator(p: u64, factors: &[u64]) -> u64 {
+    let mut g = 2;
+    while g < p {
+        let mut i = 0;
+        while i < factors.len() && mod_pow_u64_safe(g, (p - 1) / factors[i], p) != 1 {
+            i += 1;
+        }
+        if i == factors.len() {
+            return g;
+        }
+        g += 1;
+    }
+    1
+}
+
+/// **O(log(p)^2)**, square root of a modulo prime p by Tonelli-Shanks algorithm,
+/// return the smaller one of two roots, None if a is not quadratic residue
+pub fn mod_sqrt(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a < 2 || p == 2 {
+        return Some(a);
+    }
+    if mod_pow_u64_safe(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p).find(|&z| mod_pow_u64_safe(z, (p - 1) / 2, p) == p - 1)?;
+    let (mut m, mut c) = (s, mod_pow_u64_safe(z, q, p));
+    let (mut t, mut r) = (mod_pow_u64_safe(a, q, p), mod_pow_u64_safe(a, q.div_ceil(2), p));
+    // invariant r^2 = a t, and order of t divides 2^(m - 1)
+    while t != 1 {
+        let i = (1..m).find(|&i| mod_pow_u64_safe(t, 1 << i, p) == 1)?;
+        let b = mod_pow_u64_safe(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mod_mul_u64(b, b, p);
+        t = mod_mul_u64(t, c, p);
+        r = mod_mul_u64(r, b, p);
+    }
+    Some(r.min(p - r))
+}
+
+/// **O(p^(1/4) log(p) + log(p)^2 + Σ sqrt(r))** expected, where r runs over prime factors of gcd(k, p - 1),
+/// some x such that x^k ≡ a (mod p) for prime p by Adleman-Manders-Miller algorithm, None if it does not exist
+pub fn kth_root(a: u64, k: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if k == 0 {
+        return if a == 1 % p { Some(1 % p) } else { None };
+    }
+    if a == 0 {
+        return Some(0);
+    }
+    let d = k.gcd(&(p - 1));
+    let order = (p - 1) / d;
+    if mod_pow_u64_safe(a, order, p) != 1 {
+        return None;
+    }
+    // x^k = (x^d)^(k/d) and k/d is invertible modulo the order of a, so only d-th root is left
+    let c = match order {
+        1 => 1,
+        _ => {
+            let inv = inverse_mod_mul((k / d % order) as i128, order as i128)? as u64;
+            mod_pow_u64_safe(a, inv, p)
+        }
+    };
+    let factors = if d > 1 { factorize_u64(d) } else { HashMap::new() };
+    // root of coprime prime powers can be taken one by one, each root stays a residue of the others
+    Some(factors.into_iter().fold(c, |c, (r, e)| prime_power_root(c, r, e as u32, p)))
+}
+
+/// **O(log(p)^2 + sqrt(r))**, some x such that x^(r^e) ≡ a (mod p), where r^e divides p - 1 and a is r^e-th residue
+fn prime_power_root(a: u64, r: u64, e: u32, p: u64) -> u64 {
+    let (mut s, mut t) = (p - 1, 0);
+    while s.is_multiple_of(r) {
+        s /= r;
+        t += 1;
+    }
+    let re = r.pow(e);
+    // x = a^α with r^e α ≡ 1 (mod s), so the error x^(r^e) / a lies in the subgroup of order r^(t - e)
+    let alpha = match s {
+        1 => 0,
+        _ => inverse_mod_mul((re % s) as i128, s as i128).expect("r is prime to s") as u64,
+    };
+    let mut x = mod_pow_u64_safe(a, alpha, p);
+    let a_inv = inverse_mod_mul(a as i128, p as i128).expect("a is not zero") as u64;
+    let mut error = mod_mul_u64(mod_pow_u64_safe(x, re, p), a_inv, p);
+    // c generates the subgroup of order r^t, and zeta = c^(r^(t - 1)) is primitive r-th root of unity
+    let z = (2..p).find(|&z| mod_pow_u64_safe(z, (p - 1) / r, p) != 1).expect("r divides p - 1");
+    let c = mod_pow_u64_safe(z, s, p);
+    let zeta = mod_pow_u64_safe(c, r.pow(t - 1), p);
+    while error != 1 {
+        // error^(r^(j - 1)) = zeta^l is not one but error^(r^j) is one
+        let (mut j, mut power) = (1, error);
+        loop {
+            let next = mod_pow_u64_safe(power, r, p);
+            if next == 1 {
+                break;
+            }
+            power = next;
+            j += 1;
+        }
+        let l = log_root_of_unity(zeta, power, r, p);
+        // multiplying x by c^f with f = -l r^(t - e - j) removes zeta^l from error^(r^(j - 1))
+        let f = (r - l) * r.pow(t - e - j);
+        x = mod_mul_u64(x, mod_pow_u64_safe(c, f, p), p);
+        error = mod_mul_u64(error, mod_pow_u64_safe(c, f * re, p), p);
+    }
+    x
+}
+
+/// **O(sqrt(r))**, baby-step giant-step, l in [0, r) such that zeta^l ≡ x (mod p), where zeta has prime order r
+fn log_root_of_unity(zeta: u64, x: u64, r: u64, p: u64) -> u64 {
+    let step = (r as f64).sqrt().ceil() as u64 + 1;
+    let mut baby = HashMap::new();
+    let mut current = 1;
+    for j in 0..step {
+        baby.entry(current).or_insert(j);
+        current = mod_mul_u64(current, zeta, p);
+    }
+    // zeta^(-step) = zeta^(r - step % r)
+    let giant = mod_pow_u64_safe(zeta, r - step % r, p);
+    let mut current = x;
+    for i in 0..=step {
+        if let Some(&j) = baby.get(&current) {
+            return (i * step + j) % r;
+        }
+        current = mod_mul_u64(current, giant, p);
+    }
+    unreachable!("x must be a power of zeta")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,6 +372,21 @@ mod tests {
         assert_eq!(mod_pow_u64(2, 9999999, 3), 2);
     }
 
+    #[test]
+    fn test_u64_safe() {
+        assert_eq!(mod_mul_u64(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+        assert_eq!(mod_pow_u64_safe(10, 3, 1_000_000_007), 1000);
+        assert_eq!(mod_pow_u64_safe(3, 0, 1), 0);
+        // Fermat's little theorem for the largest prime below 2^64
+        let p = 18_446_744_073_709_551_557;
+        assert_eq!(mod_pow_u64_safe(123_456_789, p - 1, p), 1);
+        for a in 0..50 {
+            for b in 0..20 {
+                assert_eq!(mod_pow_u64_safe(a, b, 1_000_000_007), mod_pow_u64(a, b, 1_000_000_007));
+            }
+        }
+    }
+
     #[test]
     fn test_ex_euclid() {
         assert_eq!(ex_euclid(3, 5), ((2, -1), 1));
@@ -125,4 +418,135 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 5), (7, 1)]), Some((4, 5)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        let (p, q) = (1_000_000_007, 998_244_353);
+        assert_eq!(crt(&[(5, p), (5, q)]), Some((5, p * q)));
+        assert_eq!(crt(&[(0, p), (0, q), (0, 1_000_000_009)]), None); // lcm overflows i64
+        for x in 0..60 {
+            for m1 in 1..12 {
+                for m2 in 1..12 {
+                    let lcm = m1 * m2 / num::integer::gcd(m1, m2);
+                    assert_eq!(crt(&[(x % m1, m1), (x % m2, m2)]), Some((x % lcm, lcm)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_garner() {
+        assert_eq!(garner(&[(2, 3), (3, 5), (2, 7)], 1_000_000_007), Some(23));
+        assert_eq!(garner(&[(2, 3), (3, 5), (2, 7)], 10), Some(3));
+        assert_eq!(garner(&[(1, 4), (3, 6)], 100), None);
+        assert_eq!(garner(&[(0, 1), (4, 5)], 3), Some(1));
+        assert_eq!(garner(&[], 7), Some(0));
+        assert_eq!(garner(&[(3, 5)], 1), Some(0));
+        // x = 10^30 + 12345 is reconstructed from residues of three primes
+        let primes = [1_000_000_007u64, 998_244_353, 1_000_000_009, 754_974_721];
+        let x = 10u128.pow(30) + 12345;
+        let residues: Vec<_> = primes.iter().map(|&p| ((x % p as u128) as u64, p)).collect();
+        assert_eq!(garner(&residues, u64::MAX), Some((x % u64::MAX as u128) as u64));
+        assert_eq!(garner(&residues, 1 << 40), Some((x % (1 << 40)) as u64));
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(2, 1, 5), Some(0));
+        assert_eq!(discrete_log(2, 3, 5), Some(3));
+        let p = 1_000_000_007;
+        let x = discrete_log(5, 7, p).unwrap();
+        assert_eq!(mod_pow_u64_safe(5, x, p), 7);
+        assert!(x < p - 1);
+        assert_eq!(discrete_log(0, 0, 1), Some(0));
+        assert_eq!(discrete_log(4, 3, 8), None);
+        for m in 1..40 {
+            for a in 0..m {
+                for b in 0..m {
+                    let expected = (0..2 * m).find(|&x| mod_pow_u64_safe(a, x, m) == b % m);
+                    assert_eq!(discrete_log(a, b, m), expected, "{}^x = {} mod {}", a, b, m);
+                }
+            }
+        }
+        // a and m are not coprime and the answer is beyond the direct check
+        let m = (1 << 20) * 1_000_003;
+        let b = mod_pow_u64_safe(6, 500_000, m);
+        let x = discrete_log(6, b, m).unwrap();
+        assert!((20..=500_000).contains(&x));
+        assert_eq!(mod_pow_u64_safe(6, x, m), b);
+    }
+
+    #[test]
+    fn test_primitive_root() {
+        assert_eq!(primitive_root(2), 1);
+        assert_eq!(primitive_root(7), 3);
+        assert_eq!(primitive_root(998_244_353), 3);
+        assert_eq!(primitive_root(754_974_721), 11);
+        assert_eq!(primitive_root(1_000_000_007), 5);
+        const G: u64 = primitive_root(167_772_161);
+        assert_eq!(G, 3);
+        for &p in &[2, 7, 998_244_353, 754_974_721, 1_000_000_007] {
+            assert_eq!(primitive_root_u64(p), primitive_root(p));
+        }
+        let p = 18_446_744_073_709_551_557;
+        let g = primitive_root_u64(p);
+        assert!(factorize_u64(p - 1).keys().all(|&f| mod_pow_u64_safe(g, (p - 1) / f, p) != 1));
+    }
+
+    #[test]
+    fn test_mod_sqrt() {
+        for &p in &[2, 3, 5, 13, 17, 97, 998_244_353] {
+            let squares: std::collections::HashSet<_> =
+                (0..p.min(300)).map(|x| x * x % p).collect();
+            for a in 0..p.min(300) {
+                match mod_sqrt(a, p) {
+                    Some(r) => assert_eq!(r * r % p, a),
+                    None => assert!(p > 300 || !squares.contains(&a)),
+                }
+            }
+        }
+        assert_eq!(mod_sqrt(4, 998_244_353), Some(2));
+        assert_eq!(mod_sqrt(3, 998_244_353), None);
+        let p = 18_446_744_073_709_551_557;
+        let r = mod_sqrt(mod_mul_u64(1 << 40, 1 << 40, p), p).unwrap();
+        assert_eq!(mod_mul_u64(r, r, p), mod_mul_u64(1 << 40, 1 << 40, p));
+    }
+
+    #[test]
+    fn test_kth_root() {
+        for &p in &[2, 3, 7, 13, 97, 101] {
+            for k in 0..100 {
+                for a in 0..p {
+                    let exists = (0..p).any(|x| mod_pow_u64_safe(x, k, p) == a);
+                    match kth_root(a, k, p) {
+                        Some(x) => assert_eq!(mod_pow_u64_safe(x, k, p), a),
+                        None => assert!(!exists),
+                    }
+                }
+            }
+        }
+        let p = 998_244_353;
+        let x = kth_root(mod_pow_u64_safe(12345, 3, p), 3, p).unwrap();
+        assert_eq!(mod_pow_u64_safe(x, 3, p), mod_pow_u64_safe(12345, 3, p));
+        // p - 1 = 2^23 * 7 * 17, so 2^20-th root needs many correction steps
+        let a = mod_pow_u64_safe(3, 1 << 20, p);
+        let x = kth_root(a, 1 << 20, p).unwrap();
+        assert_eq!(mod_pow_u64_safe(x, 1 << 20, p), a);
+        assert_eq!(kth_root(3, 2, p), None);
+        // 64-bit primes, where discrete logarithm is infeasible
+        for &p in &[18_446_744_073_709_551_557u64, (1 << 61) - 1] {
+            for &k in &[2, 3, 5, 6, 7, 60, 1 << 20, 1_000_000_007] {
+                for y in 2..20 {
+                    let a = mod_pow_u64_safe(y, k, p);
+                    let x = kth_root(a, k, p).unwrap();
+                    assert_eq!(mod_pow_u64_safe(x, k, p), a);
+                }
+            }
+        }
+    }
 }
@@ -1,5 +1,8 @@
+use num::Integer;
 use std::collections::HashMap;
 
+use super::modulo::{mod_mul_u64, mod_pow_u64_safe};
+
 /// **O(n)...?**, calculate vec of primes from 0 to max
 pub fn fast_primes(n: usize) -> Vec<usize> {
     let mut primes = Vec::new();
@@ -41,6 +44,103 @@ pub fn factorization(n: u64) -> HashMap<u64, usize> {
     facts
 }
 
+/// witnesses of Miller-Rabin test that are enough for all n < 3.3 * 10^24
+const MILLER_RABIN_WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// **O(log(n))**, deterministic Miller-Rabin primality test
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = MILLER_RABIN_WITNESSES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    MILLER_RABIN_WITNESSES.iter().all(|&a| {
+        let mut x = mod_pow_u64_safe(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mod_mul_u64(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// **O(n^(1/4))** expected, a non-trivial factor of odd composite n by Pollard's rho with Brent's cycle detection
+fn pollard_brent(n: u64) -> u64 {
+    const BATCH: usize = 128;
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut ys, mut q, mut g, mut r) = (0, 2, 2, 1, 1, 1);
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                // gcd is taken once in BATCH steps for the product of differences
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mod_mul_u64(q, x.abs_diff(y), n);
+                }
+                g = q.gcd(&n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        if g == n {
+            // the batch overshot, so retry step by step from the start of it
+            loop {
+                ys = f(ys);
+                g = x.abs_diff(ys).gcd(&n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+/// **O(n^(1/4) log(n))** expected, calculate prime factorization of n by Pollard's rho
+pub fn factorize_u64(n: u64) -> HashMap<u64, usize> {
+    if n < 2 {
+        return vec![(n, 1)].into_iter().collect();
+    }
+    let (mut divided, mut facts) = (n, HashMap::new());
+    for p in 2..100 {
+        while divided.is_multiple_of(p) {
+            divided /= p;
+            *facts.entry(p).or_insert(0) += 1;
+        }
+    }
+    let mut stack = vec![divided];
+    while let Some(m) = stack.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime_u64(m) {
+            *facts.entry(m).or_insert(0) += 1;
+            continue;
+        }
+        let d = pollard_brent(m);
+        stack.push(d);
+        stack.push(m / d);
+    }
+    facts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +192,45 @@ mod tests {
         assert_eq!(factorization(60), vec![(2, 2), (3, 1), (5, 1)].into_iter().collect());
         assert_eq!(factorization(300), vec![(2, 2), (3, 1), (5, 2)].into_iter().collect());
     }
+
+    #[test]
+    fn test_is_prime_u64() {
+        let primes = fast_primes(10_000);
+        for n in 0..10_000 {
+            assert_eq!(is_prime_u64(n), primes.binary_search(&(n as usize)).is_ok());
+        }
+        assert!(is_prime_u64(1_000_000_007));
+        assert!(is_prime_u64(998_244_353));
+        assert!(is_prime_u64(18_446_744_073_709_551_557));
+        assert!(!is_prime_u64(18_446_744_073_709_551_615));
+        assert!(!is_prime_u64(3_215_031_751)); // strong pseudoprime to bases 2, 3, 5 and 7
+        assert!(!is_prime_u64(1_000_000_007 * 998_244_353));
+        assert!(!is_prime_u64(4_294_967_291 * 4_294_967_279));
+    }
+
+    #[test]
+    fn test_factorize_u64() {
+        for n in 0..2000 {
+            assert_eq!(factorize_u64(n), factorization(n));
+        }
+        assert_eq!(
+            factorize_u64(1_000_000_007 * 998_244_353),
+            vec![(1_000_000_007, 1), (998_244_353, 1)].into_iter().collect()
+        );
+        assert_eq!(
+            factorize_u64(4_294_967_291 * 4_294_967_279),
+            vec![(4_294_967_291, 1), (4_294_967_279, 1)].into_iter().collect()
+        );
+        assert_eq!(factorize_u64(1 << 63), vec![(2, 63)].into_iter().collect());
+        assert_eq!(
+            factorize_u64(999_999_937 * 999_999_937 * 11),
+            vec![(999_999_937, 2), (11, 1)].into_iter().collect()
+        );
+        assert_eq!(
+            factorize_u64(18_446_744_073_709_551_615),
+            vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6_700_417, 1)]
+                .into_iter()
+                .collect()
+        );
+    }
 }
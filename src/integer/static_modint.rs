@@ -0,0 +1,346 @@
+use num::{Num, One, Zero};
+use std::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::modulo::{inverse_mod_mul, mod_pow};
+
+/// integer modulo M, M is part of the type so that values of different moduli can not be mixed,
+/// M must be in [1, 2^32) so that product of two values fit in u64
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct StaticModInt<const M: u64> {
+    value: u64,
+}
+pub type ModInt998244353 = StaticModInt<998_244_353>;
+pub type ModInt1000000007 = StaticModInt<1_000_000_007>;
+
+impl<const M: u64> StaticModInt<M> {
+    const VALID_MODULUS: () = assert!(1 <= M && M < 1 << 32, "modulus must be in [1, 2^32)");
+
+    /// **O(1)**, make value % M
+    pub fn new(value: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::VALID_MODULUS;
+        StaticModInt { value: value % M }
+    }
+
+    /// **O(1)**, the modulus M
+    pub fn modulus() -> u64 {
+        M
+    }
+
+    /// **O(1)**, representative in [0, M)
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// **O(log(e))**, calculate self^e
+    pub fn pow(&self, e: u64) -> Self {
+        Self::new(mod_pow(self.value, e, M))
+    }
+
+    /// **O(log(M))**, multiplicative inverse if self and M are coprime, else None
+    pub fn checked_inv(&self) -> Option<Self> {
+        inverse_mod_mul(self.value as i64, M as i64).map(|inv| Self::new(inv as u64))
+    }
+
+    /// **O(log(M))**, multiplicative inverse (panic if self and M are not coprime)
+    pub fn inv(&self) -> Self {
+        self.checked_inv().expect("value must be coprime to modulus")
+    }
+}
+
+impl<const M: u64> fmt::Display for StaticModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+impl<const M: u64> fmt::Debug for StaticModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value, M)
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl<const M: u64> From<$t> for StaticModInt<M> {
+            fn from(x: $t) -> Self {
+                Self::new((x as u128 % M as u128) as u64)
+            }
+        }
+    )*};
+}
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {$(
+        impl<const M: u64> From<$t> for StaticModInt<M> {
+            /// negative value is converted into [0, M) as well
+            fn from(x: $t) -> Self {
+                Self::new((x as i128).rem_euclid(M as i128) as u64)
+            }
+        }
+    )*};
+}
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl<const M: u64> Add for StaticModInt<M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let value = self.value + rhs.value;
+        StaticModInt { value: if value >= M { value - M } else { value } }
+    }
+}
+impl<const M: u64> Sub for StaticModInt<M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let value = self.value + M - rhs.value;
+        StaticModInt { value: if value >= M { value - M } else { value } }
+    }
+}
+impl<const M: u64> Mul for StaticModInt<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        StaticModInt { value: self.value * rhs.value % M }
+    }
+}
+impl<const M: u64> Div for StaticModInt<M> {
+    type Output = Self;
+    /// panic if rhs is not invertible
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+impl<const M: u64> Rem for StaticModInt<M> {
+    type Output = Self;
+    /// remainder of the representatives in [0, M), which is meaningful for composite M as well
+    fn rem(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "remainder by zero");
+        StaticModInt { value: self.value % rhs.value }
+    }
+}
+impl<const M: u64> Neg for StaticModInt<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+impl<const M: u64> AddAssign for StaticModInt<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl<const M: u64> SubAssign for StaticModInt<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+impl<const M: u64> MulAssign for StaticModInt<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+impl<const M: u64> DivAssign for StaticModInt<M> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const M: u64> Sum for StaticModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |sum, x| sum + x)
+    }
+}
+impl<'a, const M: u64> Sum<&'a Self> for StaticModInt<M> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+impl<const M: u64> Product for StaticModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |product, x| product * x)
+    }
+}
+impl<'a, const M: u64> Product<&'a Self> for StaticModInt<M> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().product()
+    }
+}
+
+impl<const M: u64> Zero for StaticModInt<M> {
+    fn zero() -> Self {
+        Self::new(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+impl<const M: u64> One for StaticModInt<M> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+/// error when parsing string that is not integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseModIntError;
+impl fmt::Display for ParseModIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+impl std::error::Error for ParseModIntError {}
+
+impl<const M: u64> Num for StaticModInt<M> {
+    type FromStrRadixErr = ParseModIntError;
+    /// **O(|s|)**, parse integer of any length, leading '-' is allowed
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseModIntError> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() {
+            return Err(ParseModIntError);
+        }
+        let radix_mod = Self::from(radix);
+        let value = digits.chars().try_fold(Self::zero(), |value, c| {
+            c.to_digit(radix).map(|d| value * radix_mod + Self::from(d)).ok_or(ParseModIntError)
+        })?;
+        Ok(if negative { -value } else { value })
+    }
+}
+impl<const M: u64> FromStr for StaticModInt<M> {
+    type Err = ParseModIntError;
+    fn from_str(s: &str) -> Result<Self, ParseModIntError> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Mint = ModInt1000000007;
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Mint::new(1_000_000_000), Mint::new(10));
+        assert_eq!((a + b).value(), 3);
+        assert_eq!((b - a).value(), 17);
+        assert_eq!((a * b).value(), 999_999_937);
+        assert_eq!(-Mint::new(1), Mint::new(1_000_000_006));
+        assert_eq!(-Mint::zero(), Mint::zero());
+        assert_eq!(a / b * b, a);
+        assert_eq!(Mint::new(2).inv(), Mint::new(500_000_004));
+        assert_eq!(Mint::new(2).pow(10), Mint::new(1024));
+        assert_eq!(Mint::new(3).pow(1_000_000_006), Mint::one());
+        let mut c = a;
+        c += b;
+        c -= b;
+        c *= b;
+        c /= b;
+        assert_eq!(c, a);
+        assert_eq!(Mint::modulus(), 1_000_000_007);
+    }
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(Mint::from(-1i64).value(), 1_000_000_006);
+        assert_eq!(Mint::from(-1_000_000_008i64).value(), 1_000_000_006);
+        assert_eq!(Mint::from(u64::MAX).value(), (u64::MAX % 1_000_000_007));
+        assert_eq!(Mint::from(7usize), Mint::new(7));
+        assert_eq!("123".parse::<Mint>(), Ok(Mint::new(123)));
+        assert_eq!("-1".parse::<Mint>(), Ok(Mint::from(-1)));
+        assert_eq!("1000000007000000000".parse::<Mint>(), Ok(Mint::zero()));
+        assert_eq!("ff".parse::<Mint>(), Err(ParseModIntError));
+        assert_eq!("".parse::<Mint>(), Err(ParseModIntError));
+        assert_eq!(Mint::from_str_radix("ff", 16), Ok(Mint::new(255)));
+        assert_eq!(format!("{}", Mint::from(-2)), "1000000005");
+    }
+
+    #[test]
+    fn test_inverse() {
+        type M12 = StaticModInt<12>;
+        assert_eq!(M12::new(5).checked_inv(), Some(M12::new(5)));
+        assert_eq!(M12::new(4).checked_inv(), None);
+        for x in 1..998_244_353u64 / 10_000_000 {
+            let x = ModInt998244353::new(x * 10_000_019);
+            assert_eq!(x * x.inv(), ModInt998244353::one());
+        }
+    }
+
+    #[test]
+    fn test_sum_product() {
+        let v: Vec<_> = (1..=20u64).map(Mint::new).collect();
+        assert_eq!(v.iter().sum::<Mint>(), Mint::new(210));
+        let factorial: Mint = v.into_iter().product();
+        assert_eq!(factorial, Mint::new(146_326_063));
+    }
+
+    #[test]
+    fn test_num_interop() {
+        use crate::cumsum::cumsum1d::CumSum;
+        use crate::integer::counting::Counting;
+        fn power_sum<T: Num + Copy>(x: T, n: usize) -> T {
+            (0..n).fold((T::zero(), T::one()), |(sum, p), _| (sum + p, p * x)).0
+        }
+        assert_eq!(power_sum(Mint::new(2), 40), Mint::new((1u64 << 40) - 1));
+        let cumsum: CumSum<Mint> = (0..10u64).map(|x| Mint::new(x * 200_000_000)).collect();
+        assert_eq!(cumsum.interval_sum(2..5), Mint::new(1_800_000_000));
+        let counting = Counting::new(100, 998_244_353u64);
+        let combination = ModInt998244353::from(counting.combination(100, 50));
+        let factorial = |n| ModInt998244353::from(counting.factorial(n));
+        assert_eq!(combination, factorial(100) / factorial(50).pow(2));
+    }
+
+    #[test]
+    fn test_rem() {
+        type M12 = StaticModInt<12>;
+        assert_eq!(M12::new(11) % M12::new(4), M12::new(3));
+        assert_eq!(M12::new(13) % M12::new(5), M12::new(1));
+        assert_eq!(Mint::new(10) % Mint::new(3), Mint::new(1));
+        // euclidean algorithm written for Num works on the representatives
+        fn gcd<T: Num + Copy>(a: T, b: T) -> T {
+            if b.is_zero() {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+        assert_eq!(gcd(M12::new(8), M12::new(6)), M12::new(2));
+    }
+
+    #[test]
+    fn test_monoid() {
+        use crate::collections::segtree::segtree::{Monoid, SegmentTree};
+        #[derive(Clone)]
+        struct Affine(Mint, Mint);
+        impl From<(Mint, Mint)> for Affine {
+            fn from((a, b): (Mint, Mint)) -> Self {
+                Affine(a, b)
+            }
+        }
+        impl Monoid<(Mint, Mint)> for Affine {
+            fn identity() -> Self {
+                Affine(Mint::one(), Mint::zero())
+            }
+            fn operation(f: &Self, g: &Self) -> Self {
+                // apply f then g
+                Affine(f.0 * g.0, f.1 * g.0 + g.1)
+            }
+            fn into(self) -> (Mint, Mint) {
+                (self.0, self.1)
+            }
+        }
+        let maps: Vec<_> = (1..=5).map(|i| (Mint::new(i), Mint::from(-(i as i64)))).collect();
+        let tree = SegmentTree::<Affine>::new(&maps);
+        let (a, b) = tree.query(1..4);
+        let expected = maps[1..4]
+            .iter()
+            .fold((Mint::one(), Mint::zero()), |(a, b), &(c, d)| (a * c, b * c + d));
+        assert_eq!((a, b), expected);
+    }
+}
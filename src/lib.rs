@@ -11,3 +11,5 @@ pub mod graph;
 pub mod integer;
 pub mod knapsack;
 pub mod macros;
+pub mod polynomial;
+pub mod string;
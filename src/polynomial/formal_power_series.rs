@@ -0,0 +1,442 @@
+use num::{One, Zero};
+use std::{
+    iter::FromIterator,
+    ops::{Add, Div, Index, Mul, Neg, Rem, Sub},
+};
+
+use super::ntt::convolution;
+use crate::integer::{modulo::mod_sqrt, static_modint::StaticModInt};
+
+/// formal power series (or polynomial) over NTT friendly prime modulus M, coef[i] is coefficient of x^i
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct FormalPowerSeries<const M: u64> {
+    coef: Vec<StaticModInt<M>>,
+}
+impl<const M: u64> From<Vec<StaticModInt<M>>> for FormalPowerSeries<M> {
+    fn from(coef: Vec<StaticModInt<M>>) -> Self {
+        FormalPowerSeries { coef }
+    }
+}
+impl<const M: u64> FromIterator<StaticModInt<M>> for FormalPowerSeries<M> {
+    fn from_iter<I: IntoIterator<Item = StaticModInt<M>>>(iter: I) -> Self {
+        FormalPowerSeries { coef: iter.into_iter().collect() }
+    }
+}
+impl<const M: u64> Index<usize> for FormalPowerSeries<M> {
+    type Output = StaticModInt<M>;
+    fn index(&self, i: usize) -> &StaticModInt<M> {
+        &self.coef[i]
+    }
+}
+
+impl<const M: u64> FormalPowerSeries<M> {
+    /// **O(n)**, make series from coefficients
+    pub fn new(coef: &[StaticModInt<M>]) -> Self {
+        FormalPowerSeries { coef: coef.to_vec() }
+    }
+
+    /// **O(1)**, the number of stored coefficients
+    pub fn len(&self) -> usize {
+        self.coef.len()
+    }
+
+    /// **O(1)**, return true if no coefficient is stored, else return false
+    pub fn is_empty(&self) -> bool {
+        self.coef.is_empty()
+    }
+
+    /// **O(1)**, coefficients
+    pub fn coef(&self) -> &[StaticModInt<M>] {
+        &self.coef
+    }
+
+    /// **O(1)**, coefficient of x^i, zero if it is not stored
+    pub fn get(&self, i: usize) -> StaticModInt<M> {
+        self.coef.get(i).cloned().unwrap_or_else(StaticModInt::zero)
+    }
+
+    /// **O(n)**, degree as polynomial, None if it is zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coef.iter().rposition(|c| !c.is_zero())
+    }
+
+    /// **O(n)**, remove trailing zero coefficients
+    pub fn normalize(mut self) -> Self {
+        self.coef.truncate(self.degree().map_or(0, |d| d + 1));
+        self
+    }
+
+    /// **O(n)**, f mod x^n, padded by zero if it is shorter than n
+    pub fn truncate(&self, n: usize) -> Self {
+        (0..n).map(|i| self.get(i)).collect()
+    }
+
+    /// **O(n)**, reversed coefficients
+    fn reverse(&self) -> Self {
+        self.coef.iter().rev().cloned().collect()
+    }
+
+    /// **O(n)**, f'
+    pub fn derivative(&self) -> Self {
+        self.coef.iter().enumerate().skip(1).map(|(i, &c)| c * StaticModInt::from(i)).collect()
+    }
+
+    /// **O(n log(M))**, ∫f dx whose constant term is zero
+    pub fn integral(&self) -> Self {
+        let tail = self.coef.iter().enumerate().map(|(i, &c)| c / StaticModInt::from(i + 1));
+        std::iter::once(StaticModInt::zero()).chain(tail).collect()
+    }
+
+    /// **O(n)**, evaluate f(x) by Horner's method
+    pub fn evaluate(&self, x: StaticModInt<M>) -> StaticModInt<M> {
+        self.coef.iter().rev().fold(StaticModInt::zero(), |acc, &c| acc * x + c)
+    }
+
+    /// **O(n log(n))**, first n terms of 1 / f by Newton's method, f[0] must not be zero
+    pub fn inv(&self, n: usize) -> Self {
+        assert!(!self.get(0).is_zero(), "constant term must be invertible");
+        let mut g = Self::from(vec![self[0].inv()]);
+        let mut len = 1;
+        while len < n {
+            len *= 2;
+            // g = g * (2 - f * g)
+            let mut h = -(self.truncate(len) * g.clone()).truncate(len);
+            h.coef[0] += StaticModInt::from(2);
+            g = (g * h).truncate(len);
+        }
+        g.truncate(n)
+    }
+
+    /// **O(n log(n))**, first n terms of log(f), f[0] must be one
+    pub fn log(&self, n: usize) -> Self {
+        assert!(self.get(0).is_one(), "constant term must be one");
+        if n == 0 {
+            return Self::default();
+        }
+        (self.derivative().truncate(n) * self.inv(n)).truncate(n - 1).integral()
+    }
+
+    /// **O(n log(n))**, first n terms of exp(f) by Newton's method, f[0] must be zero
+    pub fn exp(&self, n: usize) -> Self {
+        assert!(self.get(0).is_zero(), "constant term must be zero");
+        let mut g = Self::from(vec![StaticModInt::one()]);
+        let mut len = 1;
+        while len < n {
+            len *= 2;
+            // g = g * (1 - log(g) + f)
+            let mut h = self.truncate(len) - g.log(len);
+            h.coef[0] += StaticModInt::one();
+            g = (g * h).truncate(len);
+        }
+        g.truncate(n)
+    }
+
+    /// **O(n log(n))**, first n terms of f^k
+    pub fn pow(&self, k: u64, n: usize) -> Self {
+        if k == 0 {
+            return Self::from(vec![StaticModInt::one()]).truncate(n);
+        }
+        let zeros = match self.coef.iter().position(|c| !c.is_zero()) {
+            Some(i) if (i as u128) * (k as u128) < n as u128 => i,
+            _ => return Self::from(vec![StaticModInt::zero(); n]),
+        };
+        // f = c x^zeros (1 + g), then f^k = c^k x^(zeros k) exp(k log(1 + g))
+        let c = self[zeros];
+        let shift = zeros * k as usize;
+        let normalized: Self = self.coef[zeros..].iter().map(|&x| x / c).collect();
+        let powered = (normalized.log(n - shift) * StaticModInt::from(k)).exp(n - shift);
+        let c_k = c.pow(k);
+        std::iter::repeat_n(StaticModInt::zero(), shift)
+            .chain(powered.coef.into_iter().map(|x| x * c_k))
+            .collect()
+    }
+
+    /// **O(n log(n))**, first n terms of g such that g^2 = f, None if it does not exist
+    pub fn sqrt(&self, n: usize) -> Option<Self> {
+        let zeros = match self.coef.iter().position(|c| !c.is_zero()) {
+            Some(i) if i / 2 < n => i,
+            _ => return Some(Self::from(vec![StaticModInt::zero(); n])),
+        };
+        if zeros % 2 == 1 {
+            return None;
+        }
+        let c = self[zeros];
+        let root = StaticModInt::new(mod_sqrt(c.value(), M)?);
+        let shift = zeros / 2;
+        let normalized: Self = self.coef[zeros..].iter().map(|&x| x / c).collect();
+        let half = StaticModInt::from(2).inv();
+        let rooted = (normalized.log(n - shift) * half).exp(n - shift);
+        Some(
+            std::iter::repeat_n(StaticModInt::zero(), shift)
+                .chain(rooted.coef.into_iter().map(|x| x * root))
+                .collect(),
+        )
+    }
+
+    /// **O(n log(n))**, quotient and remainder of polynomial division, divisor must not be zero
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let (f, g) = (self.clone().normalize(), divisor.clone().normalize());
+        assert!(!g.is_empty(), "division by zero polynomial");
+        if f.len() < g.len() {
+            return (Self::default(), f);
+        }
+        let (k, remainder_len) = (f.len() - g.len() + 1, g.len() - 1);
+        let quotient = (f.reverse().truncate(k) * g.reverse().inv(k)).truncate(k).reverse();
+        let remainder = (f - g * quotient.clone()).truncate(remainder_len).normalize();
+        (quotient.normalize(), remainder)
+    }
+
+    /// **O(n log(n)^2)**, evaluate f at every point by remainder of subproduct tree
+    pub fn evaluate_many(&self, xs: &[StaticModInt<M>]) -> Vec<StaticModInt<M>> {
+        if xs.is_empty() {
+            return Vec::new();
+        }
+        let tree = subproduct_tree(xs);
+        let size = tree.len() / 2;
+        let mut remainders = vec![Self::default(); 2 * size];
+        remainders[1] = self.div_rem(&tree[1]).1;
+        for i in 2..2 * size {
+            remainders[i] = remainders[i / 2].div_rem(&tree[i]).1;
+        }
+        (0..xs.len()).map(|j| remainders[size + j].get(0)).collect()
+    }
+
+    /// **O(n log(n)^2)**, polynomial of degree less than n that pass through (xs[i], ys[i]), xs must be distinct
+    pub fn interpolate(xs: &[StaticModInt<M>], ys: &[StaticModInt<M>]) -> Self {
+        assert_eq!(xs.len(), ys.len());
+        if xs.is_empty() {
+            return Self::default();
+        }
+        let tree = subproduct_tree(xs);
+        let size = tree.len() / 2;
+        // Lagrange weight is ys[i] / Π_{j != i} (xs[i] - xs[j]), and the denominator is derivative of Π (x - xs[j])
+        let denominators = tree[1].derivative().evaluate_many(xs);
+        let mut values = vec![Self::default(); 2 * size];
+        for (j, (&y, &d)) in ys.iter().zip(&denominators).enumerate() {
+            values[size + j] = Self::from(vec![y / d]);
+        }
+        for i in (1..size).rev() {
+            values[i] = values[2 * i].clone() * tree[2 * i + 1].clone()
+                + values[2 * i + 1].clone() * tree[2 * i].clone();
+        }
+        values.swap_remove(1).truncate(xs.len()).normalize()
+    }
+}
+
+/// **O(n log(n)^2)**, tree[size + i] = x - xs[i] and tree[i] = tree[2i] * tree[2i + 1]
+fn subproduct_tree<const M: u64>(xs: &[StaticModInt<M>]) -> Vec<FormalPowerSeries<M>> {
+    let size = xs.len().next_power_of_two();
+    let mut tree = vec![FormalPowerSeries::from(vec![StaticModInt::one()]); 2 * size];
+    for (i, &x) in xs.iter().enumerate() {
+        tree[size + i] = FormalPowerSeries::from(vec![-x, StaticModInt::one()]);
+    }
+    for i in (1..size).rev() {
+        tree[i] = tree[2 * i].clone() * tree[2 * i + 1].clone();
+    }
+    tree
+}
+
+impl<const M: u64> Add for FormalPowerSeries<M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        (0..self.len().max(rhs.len())).map(|i| self.get(i) + rhs.get(i)).collect()
+    }
+}
+impl<const M: u64> Sub for FormalPowerSeries<M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        (0..self.len().max(rhs.len())).map(|i| self.get(i) - rhs.get(i)).collect()
+    }
+}
+impl<const M: u64> Neg for FormalPowerSeries<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.coef.into_iter().map(|c| -c).collect()
+    }
+}
+impl<const M: u64> Mul for FormalPowerSeries<M> {
+    type Output = Self;
+    /// **O(n log(n))**, product by NTT
+    fn mul(self, rhs: Self) -> Self {
+        Self::from(convolution(&self.coef, &rhs.coef))
+    }
+}
+impl<const M: u64> Mul<StaticModInt<M>> for FormalPowerSeries<M> {
+    type Output = Self;
+    fn mul(self, rhs: StaticModInt<M>) -> Self {
+        self.coef.into_iter().map(|c| c * rhs).collect()
+    }
+}
+impl<const M: u64> Div for FormalPowerSeries<M> {
+    type Output = Self;
+    /// quotient of polynomial division
+    fn div(self, rhs: Self) -> Self {
+        self.div_rem(&rhs).0
+    }
+}
+impl<const M: u64> Rem for FormalPowerSeries<M> {
+    type Output = Self;
+    /// remainder of polynomial division
+    fn rem(self, rhs: Self) -> Self {
+        self.div_rem(&rhs).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::static_modint::ModInt998244353 as Mint;
+    use rand::RngExt;
+
+    type Fps = FormalPowerSeries<998_244_353>;
+
+    fn fps(coef: &[i64]) -> Fps {
+        coef.iter().map(|&c| Mint::from(c)).collect()
+    }
+
+    fn random_fps(n: usize) -> Fps {
+        let mut rng = rand::rng();
+        (0..n).map(|_| Mint::new(rng.random_range(0..998_244_353))).collect()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (f, g) = (fps(&[1, 2, 3]), fps(&[4, 5]));
+        assert_eq!(f.clone() + g.clone(), fps(&[5, 7, 3]));
+        assert_eq!(f.clone() - g.clone(), fps(&[-3, -3, 3]));
+        assert_eq!(f.clone() * g.clone(), fps(&[4, 13, 22, 15]));
+        assert_eq!(-g.clone(), fps(&[-4, -5]));
+        assert_eq!(f.derivative(), fps(&[2, 6]));
+        assert_eq!(f.derivative().integral(), fps(&[0, 2, 3]));
+        assert_eq!(f.evaluate(Mint::new(2)), Mint::new(17));
+        assert_eq!(fps(&[0, 1, 0, 0]).degree(), Some(1));
+        assert_eq!(fps(&[0, 0]).degree(), None);
+    }
+
+    #[test]
+    fn test_inv_log_exp() {
+        let f = random_fps(100);
+        let inv = f.inv(100);
+        assert_eq!((f.clone() * inv).truncate(100), fps(&[1]).truncate(100));
+        let mut g = random_fps(100);
+        g.coef[0] = Mint::zero();
+        let e = g.exp(100);
+        assert_eq!(e.log(100), g);
+        // exp(x) = Σ x^n / n!
+        let e = fps(&[0, 1]).exp(10);
+        let mut factorial_inv = [Mint::one(); 10];
+        for i in 1..10 {
+            factorial_inv[i] = factorial_inv[i - 1] / Mint::from(i);
+        }
+        assert_eq!(e.coef(), factorial_inv);
+    }
+
+    #[test]
+    fn test_pow_sqrt() {
+        let f = fps(&[0, 0, 3, 1, 4]);
+        let mut naive = fps(&[1]);
+        for k in 0..6 {
+            assert_eq!(f.pow(k, 20), naive.truncate(20));
+            naive = naive * f.clone();
+        }
+        assert_eq!(f.pow(1 << 40, 20), fps(&[0]).truncate(20));
+        let g = random_fps(50);
+        let square = (g.clone() * g.clone()).truncate(50);
+        let root = square.sqrt(50).unwrap();
+        assert!(root == g || root == -g);
+        let shifted = fps(&[0, 0, 4, 4, 1]); // (x (2 + x))^2
+        let root = shifted.sqrt(4).unwrap();
+        assert!(root == fps(&[0, 2, 1, 0]) || root == fps(&[0, -2, -1, 0]));
+        assert_eq!(fps(&[0, 1]).sqrt(3), None);
+        assert_eq!(fps(&[3]).sqrt(3), None); // 3 is not quadratic residue in 998244353
+        assert_eq!(fps(&[0, 0]).sqrt(3), Some(fps(&[0, 0, 0])));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let (n, m) = (rng.random_range(1..80), rng.random_range(1..80));
+            let (f, g) = (random_fps(n), random_fps(m).normalize());
+            if g.is_empty() {
+                continue;
+            }
+            let (q, r) = f.div_rem(&g);
+            assert!(r.degree().is_none_or(|d| d < g.degree().unwrap()));
+            assert_eq!((g * q + r).normalize(), f.normalize());
+        }
+        assert_eq!(fps(&[-1, 0, 1]) / fps(&[1, 1]), fps(&[-1, 1]));
+        assert_eq!(fps(&[2, 0, 1]) % fps(&[1, 1]), fps(&[3]));
+        assert_eq!(fps(&[1]) / fps(&[1, 1]), fps(&[]));
+    }
+
+    #[test]
+    fn test_multipoint_interpolation() {
+        let f = random_fps(70);
+        let xs: Vec<_> = (0..100u64).map(|x| Mint::new(x * x + 7)).collect();
+        let ys = f.evaluate_many(&xs);
+        for (&x, &y) in xs.iter().zip(&ys) {
+            assert_eq!(f.evaluate(x), y);
+        }
+        assert_eq!(Fps::interpolate(&xs, &ys), f.clone().normalize());
+        let xs: Vec<_> = (0..5u64).map(Mint::new).collect();
+        let ys: Vec<_> = xs.iter().map(|&x| x * x * x).collect();
+        assert_eq!(Fps::interpolate(&xs, &ys), fps(&[0, 0, 0, 1]));
+        assert_eq!(Fps::interpolate(&[], &[]), fps(&[]));
+    }
+
+    #[test]
+    fn test_partition_numbers() {
+        // Π 1 / (1 - x^k) = exp(Σ_k Σ_j x^(jk) / j)
+        let n = 50;
+        let mut f = vec![Mint::zero(); n];
+        for k in 1..n {
+            for j in (1..).take_while(|j| j * k < n) {
+                f[j * k] += Mint::from(j).inv();
+            }
+        }
+        let partitions = Fps::from(f).exp(n);
+        let expected = [1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42];
+        assert_eq!(&partitions.coef()[..11], fps(&expected).coef());
+        assert_eq!(partitions[49], Mint::new(173_525));
+    }
+
+    #[test]
+    fn test_bernoulli_numbers() {
+        // x / (e^x - 1) = Σ B_n x^n / n!
+        let n = 10;
+        let e_shifted = fps(&[0, 1]).exp(n + 1).coef()[1..].iter().cloned().collect::<Fps>();
+        let egf = e_shifted.inv(n);
+        let bernoulli: Vec<_> = (0..n)
+            .scan(Mint::one(), |f, i| {
+                if i > 0 {
+                    *f *= Mint::from(i);
+                }
+                Some(egf[i] * *f)
+            })
+            .collect();
+        let expected = [
+            Mint::one(),
+            -Mint::new(2).inv(),
+            Mint::new(6).inv(),
+            Mint::zero(),
+            -Mint::new(30).inv(),
+        ];
+        assert_eq!(&bernoulli[..5], &expected[..]);
+    }
+
+    #[test]
+    fn test_stirling_numbers() {
+        // S(n, k) = Σ_i (-1)^(k - i) i^n / (i! (k - i)!) is convolution of i^n / i! and (-1)^j / j!
+        let n = 6u64;
+        let mut factorial_inv = [Mint::one(); 7];
+        for i in 1..7 {
+            factorial_inv[i] = factorial_inv[i - 1] / Mint::from(i);
+        }
+        let a: Fps = (0..7).map(|i| Mint::from(i).pow(n) * factorial_inv[i]).collect();
+        let b: Fps =
+            (0..7).map(|j| if j % 2 == 0 { factorial_inv[j] } else { -factorial_inv[j] }).collect();
+        let stirling = (a * b).truncate(7);
+        assert_eq!(stirling, fps(&[0, 1, 31, 90, 65, 15, 1]));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod formal_power_series;
+pub mod ntt;
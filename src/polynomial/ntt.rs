@@ -0,0 +1,221 @@
+use num::{One, Zero};
+
+use crate::integer::{
+    modulo::{inverse_mod_mul, primitive_root},
+    static_modint::StaticModInt,
+};
+
+/// NTT friendly primes p = c * 2^k + 1 (k >= 24) and their product fits in u128
+const PRIMES: [u64; 3] = [754_974_721, 167_772_161, 469_762_049];
+
+/// primitive root of the modulus, computed once per M
+struct Root<const M: u64>;
+impl<const M: u64> Root<M> {
+    const G: u64 = primitive_root(M);
+}
+
+/// **O(n log(n))**, number theoretic transform in place,
+/// n must be power of 2 and divide M - 1 (M is prime)
+pub fn ntt<const M: u64>(a: &mut [StaticModInt<M>]) {
+    butterfly(a, false);
+}
+
+/// **O(n log(n))**, inverse number theoretic transform in place, which includes division by n
+pub fn intt<const M: u64>(a: &mut [StaticModInt<M>]) {
+    butterfly(a, true);
+    let inv_n = StaticModInt::<M>::from(a.len()).inv();
+    for x in a.iter_mut() {
+        *x *= inv_n;
+    }
+}
+
+/// **O(n log(n))**, iterative Cooley-Tukey butterfly with bit reversal permutation
+fn butterfly<const M: u64>(a: &mut [StaticModInt<M>], inverse: bool) {
+    let n = a.len();
+    assert!(n.is_power_of_two() && (M - 1).is_multiple_of(n as u64), "length must divide M - 1");
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits().checked_shr(usize::BITS - bits).unwrap_or(0);
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let g = StaticModInt::<M>::new(Root::<M>::G);
+    let mut len = 2;
+    while len <= n {
+        let w = g.pow((M - 1) / len as u64);
+        let w = if inverse { w.inv() } else { w };
+        for start in (0..n).step_by(len) {
+            let mut wi = StaticModInt::one();
+            for i in start..start + len / 2 {
+                let (u, v) = (a[i], a[i + len / 2] * wi);
+                a[i] = u + v;
+                a[i + len / 2] = u - v;
+                wi *= w;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// **O((n + m) log(n + m))**, c[k] = Σ a[i] * b[k - i] in NTT friendly prime modulus M
+pub fn convolution<const M: u64>(
+    a: &[StaticModInt<M>],
+    b: &[StaticModInt<M>],
+) -> Vec<StaticModInt<M>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    if a.len().min(b.len()) <= 32 {
+        // naive is faster for small input
+        let mut c = vec![StaticModInt::zero(); len];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                c[i + j] += x * y;
+            }
+        }
+        return c;
+    }
+    let n = len.next_power_of_two();
+    let (mut fa, mut fb) = (a.to_vec(), b.to_vec());
+    fa.resize(n, StaticModInt::zero());
+    fb.resize(n, StaticModInt::zero());
+    ntt(&mut fa);
+    ntt(&mut fb);
+    for (x, &y) in fa.iter_mut().zip(&fb) {
+        *x *= y;
+    }
+    intt(&mut fa);
+    fa.truncate(len);
+    fa
+}
+
+/// **O((n + m) log(n + m))**, convolution in three NTT friendly primes, restored by Garner's algorithm,
+/// result is exact if every coefficient of the true convolution is less than product of primes (about 2^85)
+fn convolution_u128(a: &[u64], b: &[u64]) -> Vec<u128> {
+    fn convolution_in<const P: u64>(a: &[u64], b: &[u64]) -> Vec<u64> {
+        let a: Vec<_> = a.iter().map(|&x| StaticModInt::<P>::new(x)).collect();
+        let b: Vec<_> = b.iter().map(|&x| StaticModInt::<P>::new(x)).collect();
+        convolution(&a, &b).into_iter().map(|x| x.value()).collect()
+    }
+    let c0 = convolution_in::<{ PRIMES[0] }>(a, b);
+    let c1 = convolution_in::<{ PRIMES[1] }>(a, b);
+    let c2 = convolution_in::<{ PRIMES[2] }>(a, b);
+    let (p0, p1, p2) = (PRIMES[0] as u128, PRIMES[1] as u128, PRIMES[2] as u128);
+    let inv = |x: u128, p: u128| inverse_mod_mul(x as i64, p as i64).expect("coprime") as u128;
+    let (p0_inv_p1, p01_inv_p2) = (inv(p0 % p1, p1), inv(p0 * p1 % p2, p2));
+    (0..c0.len())
+        .map(|i| {
+            let (r0, r1, r2) = (c0[i] as u128, c1[i] as u128, c2[i] as u128);
+            // x = r0 + p0 * t1 + p0 * p1 * t2
+            let t1 = (r1 + p1 - r0 % p1) * p0_inv_p1 % p1;
+            let x01 = r0 + p0 * t1;
+            let t2 = (r2 + p2 - x01 % p2) * p01_inv_p2 % p2;
+            x01 + p0 * p1 * t2
+        })
+        .collect()
+}
+
+/// **O((n + m) log(n + m))**, convolution mod arbitrary modulo in [1, 2^32),
+/// n * modulo^2 must be less than about 2^85
+pub fn convolution_mod(a: &[u64], b: &[u64], modulo: u64) -> Vec<u64> {
+    let a: Vec<_> = a.iter().map(|&x| x % modulo).collect();
+    let b: Vec<_> = b.iter().map(|&x| x % modulo).collect();
+    convolution_u128(&a, &b).into_iter().map(|x| (x % modulo as u128) as u64).collect()
+}
+
+/// **O((n + m) log(n + m))**, exact convolution if every coefficient of the result fits in u64
+pub fn convolution_u64(a: &[u64], b: &[u64]) -> Vec<u64> {
+    convolution_u128(a, b).into_iter().map(|x| x as u64).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::static_modint::ModInt998244353;
+    use rand::RngExt;
+
+    fn naive(a: &[u64], b: &[u64], modulo: u128) -> Vec<u128> {
+        if a.is_empty() || b.is_empty() {
+            return Vec::new();
+        }
+        let mut c = vec![0; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                c[i + j] = (c[i + j] + x as u128 * y as u128) % modulo;
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn test_primitive_root() {
+        assert_eq!(Root::<998_244_353>::G, 3);
+        assert_eq!(Root::<754_974_721>::G, 11);
+        assert_eq!(Root::<167_772_161>::G, 3);
+        assert_eq!(Root::<469_762_049>::G, 3);
+    }
+
+    #[test]
+    fn test_ntt_inverse() {
+        let a: Vec<_> = (0..16u64).map(|x| ModInt998244353::new(x * x + 1)).collect();
+        let mut b = a.clone();
+        ntt(&mut b);
+        assert_eq!(b[0], a.iter().sum());
+        intt(&mut b);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_convolution() {
+        let a: Vec<_> = [1, 2, 3, 4].iter().map(|&x| ModInt998244353::new(x)).collect();
+        let b: Vec<_> = [5, 6, 7, 8, 9].iter().map(|&x| ModInt998244353::new(x)).collect();
+        let c: Vec<_> = convolution(&a, &b).into_iter().map(|x| x.value()).collect();
+        assert_eq!(c, [5, 16, 34, 60, 70, 70, 59, 36]);
+        assert_eq!(convolution(&a, &[]), []);
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let (n, m) = (rng.random_range(1..300), rng.random_range(1..300));
+            let a: Vec<u64> = (0..n).map(|_| rng.random_range(0..998_244_353)).collect();
+            let b: Vec<u64> = (0..m).map(|_| rng.random_range(0..998_244_353)).collect();
+            let (ma, mb): (Vec<_>, Vec<_>) = (
+                a.iter().map(|&x| ModInt998244353::new(x)).collect(),
+                b.iter().map(|&x| ModInt998244353::new(x)).collect(),
+            );
+            let c: Vec<_> = convolution(&ma, &mb).into_iter().map(|x| x.value() as u128).collect();
+            assert_eq!(c, naive(&a, &b, 998_244_353));
+        }
+    }
+
+    #[test]
+    fn test_convolution_mod() {
+        let mut rng = rand::rng();
+        for &modulo in &[1_000_000_007, 2, 1, (1 << 32) - 1] {
+            for _ in 0..10 {
+                let (n, m) = (rng.random_range(1..200), rng.random_range(1..200));
+                let a: Vec<u64> = (0..n).map(|_| rng.random_range(0..1 << 40)).collect();
+                let b: Vec<u64> = (0..m).map(|_| rng.random_range(0..1 << 40)).collect();
+                let expected: Vec<_> =
+                    naive(&a, &b, modulo as u128).into_iter().map(|x| x as u64).collect();
+                assert_eq!(convolution_mod(&a, &b, modulo), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convolution_u64() {
+        let mut rng = rand::rng();
+        for _ in 0..10 {
+            let (n, m) = (rng.random_range(1..200), rng.random_range(1..200));
+            let a: Vec<u64> = (0..n).map(|_| rng.random_range(0..1 << 28)).collect();
+            let b: Vec<u64> = (0..m).map(|_| rng.random_range(0..1 << 28)).collect();
+            let expected: Vec<_> = naive(&a, &b, u128::MAX).into_iter().map(|x| x as u64).collect();
+            assert_eq!(convolution_u64(&a, &b), expected);
+        }
+        assert_eq!(
+            convolution_u64(&[u32::MAX as u64], &[u32::MAX as u64]),
+            [(u32::MAX as u64).pow(2)]
+        );
+    }
+}
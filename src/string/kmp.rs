@@ -0,0 +1,112 @@
+/// **O(n)**, pi[i] is the length of longest proper border (prefix that is also suffix) of s[..=i]
+pub fn prefix_function<T: Eq>(s: &[T]) -> Vec<usize> {
+    let mut pi = vec![0; s.len()];
+    for i in 1..s.len() {
+        let mut k = pi[i - 1];
+        while k > 0 && s[i] != s[k] {
+            k = pi[k - 1];
+        }
+        if s[i] == s[k] {
+            k += 1;
+        }
+        pi[i] = k;
+    }
+    pi
+}
+
+/// **O(n+m)**, find all starting positions i such that text[i..i + pattern.len()] == pattern
+pub fn find_all<T: Eq>(pattern: &[T], text: &[T]) -> Vec<usize> {
+    let m = pattern.len();
+    if m == 0 {
+        return (0..=text.len()).collect();
+    }
+    let pi = prefix_function(pattern);
+    let (mut positions, mut k) = (Vec::new(), 0);
+    for (i, t) in text.iter().enumerate() {
+        while k > 0 && (k == m || t != &pattern[k]) {
+            k = pi[k - 1];
+        }
+        if t == &pattern[k] {
+            k += 1;
+        }
+        if k == m {
+            positions.push(i + 1 - m);
+        }
+    }
+    positions
+}
+
+/// **O(n)**, minimal p such that s[i] == s[i + p] for all i (s need not be repetition of s[..p])
+pub fn minimal_period<T: Eq>(s: &[T]) -> usize {
+    match prefix_function(s).last() {
+        Some(&border) => s.len() - border,
+        None => 0,
+    }
+}
+
+/// **O(n)**, lengths of all proper borders of s in ascending order (empty border is excluded)
+pub fn borders<T: Eq>(s: &[T]) -> Vec<usize> {
+    let pi = prefix_function(s);
+    let mut borders = Vec::new();
+    let mut k = pi.last().cloned().unwrap_or(0);
+    while k > 0 {
+        borders.push(k);
+        k = pi[k - 1];
+    }
+    borders.reverse();
+    borders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_function() {
+        let s: Vec<_> = "abacaba".chars().collect();
+        assert_eq!(prefix_function(&s), [0, 0, 1, 0, 1, 2, 3]);
+        let s: Vec<_> = "aabaaab".chars().collect();
+        assert_eq!(prefix_function(&s), [0, 1, 0, 1, 2, 2, 3]);
+        assert_eq!(prefix_function(&[0; 0]), []);
+    }
+
+    #[test]
+    fn test_find_all() {
+        let text: Vec<_> = "abababcab".chars().collect();
+        let pattern: Vec<_> = "abab".chars().collect();
+        assert_eq!(find_all(&pattern, &text), [0, 2]);
+        assert_eq!(find_all(&[1, 1], &[1, 1, 1, 2, 1, 1]), [0, 1, 4]);
+        assert_eq!(find_all(&[3], &[1, 2]), []);
+        assert_eq!(find_all(&[1, 2, 3], &[1, 2]), []);
+        assert_eq!(find_all(&[], &[1, 2]), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_find_all_naive() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let text: Vec<u8> =
+                (0..rng.random_range(0..40)).map(|_| rng.random_range(0..2)).collect();
+            let pattern: Vec<u8> =
+                (0..rng.random_range(1..4)).map(|_| rng.random_range(0..2)).collect();
+            let naive: Vec<_> =
+                (0..text.len()).filter(|&i| text[i..].starts_with(&pattern)).collect();
+            assert_eq!(find_all(&pattern, &text), naive);
+        }
+    }
+
+    #[test]
+    fn test_period_borders() {
+        let s: Vec<_> = "abcabcab".chars().collect();
+        assert_eq!(minimal_period(&s), 3);
+        assert_eq!(borders(&s), [2, 5]);
+        let s: Vec<_> = "aaaa".chars().collect();
+        assert_eq!(minimal_period(&s), 1);
+        assert_eq!(borders(&s), [1, 2, 3]);
+        assert_eq!(minimal_period(&[1, 2, 3]), 3);
+        assert_eq!(borders(&[1, 2, 3]), []);
+        assert_eq!(minimal_period(&[0; 0]), 0);
+        assert_eq!(borders(&[0; 0]), []);
+    }
+}
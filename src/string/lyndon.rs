@@ -0,0 +1,124 @@
+use std::{cmp::Ordering, ops::Range};
+
+/// **O(n)**, judge s is Lyndon word (strictly smaller than all of its proper rotations) or not
+pub fn is_lyndon<T: Ord>(s: &[T]) -> bool {
+    !s.is_empty() && lyndon_factorization(s).len() == 1
+}
+
+/// **O(n)**, Lyndon factorization by Duval's algorithm,
+/// s is split into non-increasing sequence of Lyndon words
+pub fn lyndon_factorization<T: Ord>(s: &[T]) -> Vec<Range<usize>> {
+    let n = s.len();
+    let (mut i, mut factors) = (0, Vec::new());
+    while i < n {
+        let (mut j, mut k) = (i + 1, i);
+        while j < n && s[k] <= s[j] {
+            k = if s[k] < s[j] { i } else { k + 1 };
+            j += 1;
+        }
+        // s[i..j] is repetition of Lyndon word of length j - k and its prefix
+        while i <= k {
+            factors.push(i..i + j - k);
+            i += j - k;
+        }
+    }
+    factors
+}
+
+/// **O(n)**, start of the lexicographically minimum rotation, the smallest one if there are some
+pub fn minimum_rotation<T: Ord>(s: &[T]) -> usize {
+    booth(s, |x, y| x.cmp(y))
+}
+
+/// **O(n)**, start of the lexicographically maximum rotation, the smallest one if there are some
+pub fn maximum_rotation<T: Ord>(s: &[T]) -> usize {
+    booth(s, |x, y| y.cmp(x))
+}
+
+/// **O(n)**, canonical form of cyclic sequence, which is the minimum rotation
+pub fn canonical_rotation<T: Ord + Clone>(s: &[T]) -> Vec<T> {
+    let start = minimum_rotation(s);
+    s[start..].iter().chain(&s[..start]).cloned().collect()
+}
+
+/// **O(n)**, Booth's algorithm, failure function of KMP over s + s with the candidate start
+fn booth<T, F>(s: &[T], cmp: F) -> usize
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let n = s.len();
+    // failure[j] is the length of the longest proper border of rotation from k of length j + 1, minus one
+    let mut failure: Vec<isize> = vec![-1; 2 * n];
+    let mut k = 0;
+    for j in 1..2 * n {
+        let x = &s[j % n];
+        let mut i = failure[j - k - 1];
+        let matched = loop {
+            match cmp(x, &s[(k as isize + i + 1) as usize % n]) {
+                Ordering::Equal => break true,
+                Ordering::Less => k = (j as isize - i - 1) as usize,
+                Ordering::Greater => (),
+            }
+            if i == -1 {
+                break false;
+            }
+            i = failure[i as usize];
+        };
+        failure[j - k] = if matched { i + 1 } else { -1 };
+    }
+    k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lyndon_factorization() {
+        let s: Vec<_> = "abbababbab".chars().collect();
+        assert_eq!(lyndon_factorization(&s), [0..3, 3..8, 8..10]);
+        let s: Vec<_> = "aab".chars().collect();
+        assert_eq!(lyndon_factorization(&s).len(), 1);
+        assert!(is_lyndon(&s));
+        assert_eq!(lyndon_factorization(&[3, 2, 2, 1]), [0..1, 1..2, 2..3, 3..4]);
+        assert!(!is_lyndon(&[1, 1]));
+        assert_eq!(lyndon_factorization::<u8>(&[]), []);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let s: Vec<_> = "bbaaccaadd".chars().collect();
+        assert_eq!(minimum_rotation(&s), 2);
+        assert_eq!(maximum_rotation(&s), 8);
+        assert_eq!(canonical_rotation(&s).into_iter().collect::<String>(), "aaccaaddbb");
+        assert_eq!(minimum_rotation(&[1, 2, 1, 2]), 0);
+        assert_eq!(maximum_rotation(&[1, 2, 1, 2]), 1);
+        assert_eq!(minimum_rotation::<u8>(&[]), 0);
+    }
+
+    #[test]
+    fn test_random_naive() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..300 {
+            let s: Vec<u8> = (0..rng.random_range(1..20)).map(|_| rng.random_range(0..3)).collect();
+            let n = s.len();
+            let rotation =
+                |i: usize| -> Vec<u8> { s[i..].iter().chain(&s[..i]).cloned().collect() };
+            let min = (0..n).min_by_key(|&i| rotation(i)).unwrap();
+            let max = (0..n).min_by_key(|&i| std::cmp::Reverse(rotation(i))).unwrap();
+            assert_eq!(minimum_rotation(&s), min);
+            assert_eq!(maximum_rotation(&s), max);
+            let factors = lyndon_factorization(&s);
+            assert_eq!(factors.first().map(|r| r.start), Some(0));
+            assert_eq!(factors.last().map(|r| r.end), Some(n));
+            assert!(factors
+                .windows(2)
+                .all(|w| w[0].end == w[1].start && s[w[0].clone()] >= s[w[1].clone()]));
+            for range in factors {
+                let word = &s[range];
+                assert!((1..word.len()).all(|i| word < &[&word[i..], &word[..i]].concat()[..]));
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::ops::Range;
+
+/// **O(n)**, odd[i] is the maximum r that s[i + 1 - r..i + r] is palindrome (length 2r - 1)
+pub fn manacher_odd<T: Eq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut odd = vec![0; n];
+    let (mut l, mut r) = (0, 0); // rightmost found palindrome s[l..r]
+    for i in 0..n {
+        let mut k = if i < r { odd[l + r - 1 - i].min(r - i) } else { 1 };
+        while i >= k && i + k < n && s[i - k] == s[i + k] {
+            k += 1;
+        }
+        odd[i] = k;
+        if i + k > r {
+            l = i + 1 - k;
+            r = i + k;
+        }
+    }
+    odd
+}
+
+/// **O(n)**, even[i] is the maximum r that s[i - r..i + r] is palindrome (center is between i - 1 and i)
+pub fn manacher_even<T: Eq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut even = vec![0; n];
+    let (mut l, mut r) = (0, 0); // rightmost found palindrome s[l..r]
+    for i in 0..n {
+        let mut k = if i < r { even[l + r - i].min(r - i) } else { 0 };
+        while i > k && i + k < n && s[i - k - 1] == s[i + k] {
+            k += 1;
+        }
+        even[i] = k;
+        if i + k > r {
+            l = i - k;
+            r = i + k;
+        }
+    }
+    even
+}
+
+/// **O(n)**, range of the longest palindromic substring, leftmost one if there are some
+pub fn longest_palindrome<T: Eq>(s: &[T]) -> Range<usize> {
+    let (odd, even) = (manacher_odd(s), manacher_even(s));
+    let odd_ranges = odd.iter().enumerate().map(|(i, &r)| i + 1 - r..i + r);
+    let even_ranges = even.iter().enumerate().map(|(i, &r)| i - r..i + r);
+    odd_ranges.chain(even_ranges).fold(0..0, |longest, range| {
+        if (range.len(), longest.start) > (longest.len(), range.start) {
+            range
+        } else {
+            longest
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_palindrome<T: Eq>(s: &[T]) -> bool {
+        s.iter().eq(s.iter().rev())
+    }
+
+    #[test]
+    fn test_manacher() {
+        let s: Vec<_> = "abaaababa".chars().collect();
+        assert_eq!(manacher_odd(&s), vec![1, 2, 1, 4, 1, 2, 3, 2, 1]);
+        assert_eq!(manacher_even(&s), vec![0, 0, 0, 1, 1, 0, 0, 0, 0]);
+        assert_eq!(longest_palindrome(&s), 0..7);
+        assert_eq!(longest_palindrome(&"abba".chars().collect::<Vec<_>>()), 0..4);
+        assert_eq!(longest_palindrome::<char>(&[]), 0..0);
+        assert_eq!(longest_palindrome(&[1, 2, 3]), 0..1);
+    }
+
+    #[test]
+    fn test_manacher_naive() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(0..30);
+            let s: Vec<u8> = (0..n).map(|_| rng.random_range(0..2)).collect();
+            let (odd, even) = (manacher_odd(&s), manacher_even(&s));
+            for i in 0..n {
+                let r = (1..=n)
+                    .take_while(|&r| {
+                        r <= i + 1 && i + r <= n && is_palindrome(&s[i + 1 - r..i + r])
+                    })
+                    .last();
+                assert_eq!(odd[i], r.unwrap());
+                let r = (0..=n)
+                    .take_while(|&r| r <= i && i + r <= n && is_palindrome(&s[i - r..i + r]))
+                    .last();
+                assert_eq!(even[i], r.unwrap());
+            }
+            let longest = (0..=n)
+                .flat_map(|l| (l..=n).map(move |r| l..r))
+                .filter(|r| is_palindrome(&s[r.clone()]));
+            assert_eq!(longest_palindrome(&s).len(), longest.map(|r| r.len()).max().unwrap());
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod kmp;
+pub mod lyndon;
+pub mod manacher;
+pub mod palindromic_tree;
+pub mod rolling_hash;
+pub mod suffix_array;
+pub mod suffix_automaton;
+pub mod z_algorithm;
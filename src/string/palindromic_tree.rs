@@ -0,0 +1,222 @@
+use std::{collections::HashMap, hash::Hash, ops::Range};
+
+type NodeId = usize;
+const IMAGINARY: NodeId = 0; // root of odd palindromes, its length is regarded as -1
+const EMPTY: NodeId = 1; // root of even palindromes
+
+#[derive(Debug)]
+struct Node<T> {
+    children: HashMap<T, NodeId>,
+    len: usize,
+    link: NodeId, // longest proper palindromic suffix
+    depth: usize, // number of non-empty palindromic suffixes including itself
+    count: usize, // number of indices that this node is the longest palindromic suffix
+    end: usize,   // end (exclusive) of the first occurrence
+}
+
+/// palindromic tree (eertree), each node except two roots is a distinct palindromic substring
+#[derive(Debug)]
+pub struct PalindromicTree<T> {
+    s: Vec<T>,
+    nodes: Vec<Node<T>>,
+    suffixes: Vec<NodeId>, // suffixes[i] is the longest palindromic suffix of s[..i + 1]
+}
+impl<T> Default for PalindromicTree<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PalindromicTree<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// **O(1)**, make tree of empty string
+    pub fn new() -> Self {
+        let root =
+            |link| Node { children: HashMap::new(), len: 0, link, depth: 0, count: 0, end: 0 };
+        PalindromicTree {
+            s: Vec::new(),
+            nodes: vec![root(IMAGINARY), root(IMAGINARY)],
+            suffixes: Vec::new(),
+        }
+    }
+
+    /// **O(n)**, build tree of s
+    pub fn build(s: &[T]) -> Self {
+        let mut tree = Self::new();
+        for x in s {
+            tree.push(x.clone());
+        }
+        tree
+    }
+
+    /// **O(1)** amortized, append x to the string, if new palindrome appear then return true
+    pub fn push(&mut self, x: T) -> bool {
+        let i = self.s.len();
+        self.s.push(x.clone());
+        let mut current = self.suffixes.last().cloned().unwrap_or(EMPTY);
+        current = self.extendable(current, i);
+        if let Some(&next) = self.nodes[current].children.get(&x) {
+            self.nodes[next].count += 1;
+            self.suffixes.push(next);
+            return false;
+        }
+        let len = if current == IMAGINARY { 1 } else { self.nodes[current].len + 2 };
+        let link = if len == 1 {
+            EMPTY
+        } else {
+            let suffix = self.extendable(self.nodes[current].link, i);
+            self.nodes[suffix].children[&x]
+        };
+        let next = self.nodes.len();
+        let depth = self.nodes[link].depth + 1;
+        self.nodes.push(Node { children: HashMap::new(), len, link, depth, count: 1, end: i + 1 });
+        self.nodes[current].children.insert(x, next);
+        self.suffixes.push(next);
+        true
+    }
+
+    /// **O(1)** amortized, follow suffix links until x s[i] (= s[i - len - 1]) can extend the node
+    fn extendable(&self, mut current: NodeId, i: usize) -> NodeId {
+        loop {
+            let len = self.nodes[current].len;
+            if current == IMAGINARY || (i > len && self.s[i - len - 1] == self.s[i]) {
+                return current;
+            }
+            current = self.nodes[current].link;
+        }
+    }
+
+    /// **O(1)**, return length of the string
+    pub fn len(&self) -> usize {
+        self.s.len()
+    }
+
+    /// **O(1)**, return true if the string is empty, else return false
+    pub fn is_empty(&self) -> bool {
+        self.s.is_empty()
+    }
+
+    /// **O(1)**, the number of distinct non-empty palindromic substrings
+    pub fn distinct_count(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    /// **O(#distinct)**, (range of the first occurrence, the number of occurrences) for each distinct palindrome
+    pub fn palindromes(&self) -> Vec<(Range<usize>, usize)> {
+        let mut counts: Vec<_> = self.nodes.iter().map(|node| node.count).collect();
+        // link always points to older node, so reverse order of creation is topological order
+        for v in (2..self.nodes.len()).rev() {
+            counts[self.nodes[v].link] += counts[v];
+        }
+        (2..self.nodes.len())
+            .map(|v| (self.nodes[v].end - self.nodes[v].len..self.nodes[v].end, counts[v]))
+            .collect()
+    }
+
+    /// **O(1)**, length of the longest palindrome that end at index i (s[i] is the last element)
+    pub fn longest_suffix(&self, i: usize) -> usize {
+        self.nodes[self.suffixes[i]].len
+    }
+
+    /// **O(1)**, the number of palindromes that end at index i
+    pub fn count_ending_at(&self, i: usize) -> usize {
+        self.nodes[self.suffixes[i]].depth
+    }
+
+    /// **O(#palindromes)**, lengths of palindromes that end at index i in descending order
+    pub fn ending_at(&self, i: usize) -> Vec<usize> {
+        let mut lengths = Vec::new();
+        let mut current = self.suffixes[i];
+        while current != EMPTY && current != IMAGINARY {
+            lengths.push(self.nodes[current].len);
+            current = self.nodes[current].link;
+        }
+        lengths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_palindrome<T: Eq>(s: &[T]) -> bool {
+        s.iter().eq(s.iter().rev())
+    }
+
+    #[test]
+    fn test_palindromic_tree() {
+        let s: Vec<_> = "eertree".chars().collect();
+        let tree = PalindromicTree::build(&s);
+        assert_eq!(tree.distinct_count(), 7);
+        let mut palindromes: Vec<_> = tree
+            .palindromes()
+            .into_iter()
+            .map(|(range, count)| (s[range].iter().collect::<String>(), count))
+            .collect();
+        palindromes.sort();
+        let expected =
+            [("e", 4), ("ee", 2), ("eertree", 1), ("ertre", 1), ("r", 2), ("rtr", 1), ("t", 1)];
+        assert_eq!(
+            palindromes,
+            expected.iter().map(|&(p, c)| (p.to_string(), c)).collect::<Vec<_>>()
+        );
+        assert_eq!(tree.ending_at(6), vec![7, 2, 1]);
+        assert_eq!(tree.count_ending_at(6), 3);
+        assert_eq!(tree.longest_suffix(4), 3);
+    }
+
+    #[test]
+    fn test_push() {
+        let mut tree = PalindromicTree::new();
+        assert!(tree.is_empty());
+        for _ in 0..4 {
+            assert!(tree.push(1));
+        }
+        assert_eq!(tree.distinct_count(), 4);
+        assert_eq!(tree.ending_at(3), vec![4, 3, 2, 1]);
+        assert!(tree.push(2));
+        assert!(tree.push(1));
+        assert!(tree.push(1));
+        assert_eq!(tree.distinct_count(), 7);
+        assert_eq!(tree.len(), 7);
+        assert!(tree.push(3));
+        assert!(!tree.push(2));
+        assert_eq!(tree.distinct_count(), 8);
+    }
+
+    #[test]
+    fn test_palindromic_tree_naive() {
+        use rand::RngExt;
+        use std::collections::HashMap;
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let n = rng.random_range(0..30);
+            let s: Vec<u8> = (0..n).map(|_| rng.random_range(0..3)).collect();
+            let tree = PalindromicTree::build(&s);
+            let mut naive = HashMap::new();
+            for l in 0..n {
+                for r in l + 1..=n {
+                    if is_palindrome(&s[l..r]) {
+                        *naive.entry(&s[l..r]).or_insert(0) += 1;
+                    }
+                }
+            }
+            assert_eq!(tree.distinct_count(), naive.len());
+            for (range, count) in tree.palindromes() {
+                assert_eq!(naive[&s[range]], count);
+            }
+            for i in 0..n {
+                let lengths: Vec<_> =
+                    (1..=i + 1).rev().filter(|&len| is_palindrome(&s[i + 1 - len..=i])).collect();
+                assert_eq!(tree.ending_at(i), lengths);
+                assert_eq!(tree.count_ending_at(i), lengths.len());
+                assert_eq!(tree.longest_suffix(i), lengths[0]);
+            }
+        }
+    }
+}
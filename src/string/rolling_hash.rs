@@ -0,0 +1,312 @@
+use std::{
+    cmp::Ordering,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+    ops::{Bound, Range, RangeBounds},
+};
+
+use rand::RngExt;
+
+use crate::algo::bisect::bisect;
+
+/// modulo of rolling hash, 2^61 - 1 is prime and multiplication can be reduced by shift
+pub const MODULO: u64 = (1 << 61) - 1;
+
+thread_local! {
+    // bases and element hasher are chosen randomly once, so that adversarial input cannot be made
+    static RANDOM_STATE: (u64, u64, RandomState) = {
+        let mut rng = rand::rng();
+        (rng.random_range(1 << 16..MODULO - 1), rng.random_range(1 << 16..MODULO - 1), RandomState::new())
+    };
+}
+
+/// **O(1)**, calculate a * b mod 2^61 - 1
+pub fn mul_mod(a: u64, b: u64) -> u64 {
+    let t = a as u128 * b as u128;
+    let t = (t >> 61) as u64 + (t as u64 & MODULO);
+    if t >= MODULO {
+        t - MODULO
+    } else {
+        t
+    }
+}
+
+/// **O(1)**, calculate a + b mod 2^61 - 1
+pub fn add_mod(a: u64, b: u64) -> u64 {
+    let t = a + b;
+    if t >= MODULO {
+        t - MODULO
+    } else {
+        t
+    }
+}
+
+/// **O(1)**, calculate a - b mod 2^61 - 1
+pub fn sub_mod(a: u64, b: u64) -> u64 {
+    add_mod(a, MODULO - b)
+}
+
+/// **O(log(e))**, calculate a^e mod 2^61 - 1
+pub fn pow_mod(a: u64, e: usize) -> u64 {
+    match e {
+        0 => 1,
+        _ => {
+            let half = pow_mod(a, e / 2);
+            let square = mul_mod(half, half);
+            if e.is_multiple_of(2) {
+                square
+            } else {
+                mul_mod(square, a)
+            }
+        }
+    }
+}
+
+/// **O(1)**, random base shared in this thread
+pub fn base() -> u64 {
+    RANDOM_STATE.with(|state| state.0)
+}
+
+/// **O(1)**, hash value of an element in [1, 2^61 - 1)
+fn element_hash<T: Hash>(x: &T) -> u64 {
+    RANDOM_STATE.with(|state| state.2.hash_one(x) % (MODULO - 1) + 1)
+}
+
+/// hashable key of substring, which can be used as key of HashMap instead of the substring itself,
+/// keys made with different bases are never equal
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubstringKey {
+    pub hash: u64,
+    pub len: usize,
+    pub base: u64,
+}
+impl SubstringKey {
+    /// **O(log(|other|))**, key of concatenated string self + other, both keys must have the same base
+    pub fn concat(&self, other: &Self) -> Self {
+        assert_eq!(self.base, other.base, "keys of different bases cannot be concatenated");
+        let hash = add_mod(mul_mod(self.hash, pow_mod(self.base, other.len)), other.hash);
+        SubstringKey { hash, len: self.len + other.len, base: self.base }
+    }
+}
+
+pub struct RollingHash<T> {
+    s: Vec<T>,
+    base: u64,
+    prefix: Vec<u64>, // prefix[i] is hash of s[..i]
+    pow: Vec<u64>,    // pow[i] is base^i
+}
+impl<T: Hash + Clone> RollingHash<T> {
+    /// **O(n)**, calculate prefix hashes with the base shared in this thread
+    pub fn new(s: &[T]) -> Self {
+        Self::with_base(s, base())
+    }
+
+    /// **O(n)**, calculate prefix hashes with given base
+    pub fn with_base(s: &[T], base: u64) -> Self {
+        let (mut prefix, mut pow) = (vec![0; s.len() + 1], vec![1; s.len() + 1]);
+        for (i, x) in s.iter().enumerate() {
+            prefix[i + 1] = add_mod(mul_mod(prefix[i], base), element_hash(x));
+            pow[i + 1] = mul_mod(pow[i], base);
+        }
+        RollingHash { s: s.to_vec(), base, prefix, pow }
+    }
+
+    /// **O(1)**, return length of the string
+    pub fn len(&self) -> usize {
+        self.s.len()
+    }
+
+    /// **O(1)**, return true if the string is empty, else return false
+    pub fn is_empty(&self) -> bool {
+        self.s.is_empty()
+    }
+
+    /// **O(1)**, range to index half interval [left, right).
+    pub fn indices<R: RangeBounds<usize>>(&self, range: R) -> (usize, usize) {
+        let left = match range.start_bound() {
+            Bound::Unbounded => 0,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Included(&l) => l,
+        };
+        let right = match range.end_bound() {
+            Bound::Unbounded => self.len(),
+            Bound::Excluded(&r) => r.min(self.len()),
+            Bound::Included(&r) => (r + 1).min(self.len()),
+        };
+        assert!(left <= right);
+        (left, right)
+    }
+
+    /// **O(1)**, hash of substring
+    pub fn hash<R: RangeBounds<usize>>(&self, range: R) -> u64 {
+        let (l, r) = self.indices(range);
+        sub_mod(self.prefix[r], mul_mod(self.prefix[l], self.pow[r - l]))
+    }
+
+    /// **O(1)**, hashable key of substring
+    pub fn key<R: RangeBounds<usize>>(&self, range: R) -> SubstringKey {
+        let (l, r) = self.indices(range);
+        SubstringKey { hash: self.hash(l..r), len: r - l, base: self.base }
+    }
+
+    /// **O(log(n))**, length of longest common prefix of self[i..] and other[j..]
+    pub fn lcp(&self, i: usize, other: &Self, j: usize) -> usize {
+        let max = (self.len() - i).min(other.len() - j);
+        let differ = |&len: &usize| self.hash(i..i + len) != other.hash(j..j + len);
+        if !differ(&max) {
+            max
+        } else {
+            bisect(0..=max, differ).expect("empty prefix is same and whole prefix differ") - 1
+        }
+    }
+
+    /// **O(log(n))**, compare self[range] and other[other_range] in lexicographic order
+    pub fn compare<R1, R2>(&self, range: R1, other: &Self, other_range: R2) -> Ordering
+    where
+        T: Ord,
+        R1: RangeBounds<usize>,
+        R2: RangeBounds<usize>,
+    {
+        let ((l1, r1), (l2, r2)) = (self.indices(range), other.indices(other_range));
+        let lcp = self.lcp(l1, other, l2).min(r1 - l1).min(r2 - l2);
+        match (l1 + lcp < r1, l2 + lcp < r2) {
+            (true, true) => self.s[l1 + lcp].cmp(&other.s[l2 + lcp]),
+            (l, r) => l.cmp(&r),
+        }
+    }
+}
+
+/// 2-dimensional rolling hash, rows and columns have different bases
+pub struct GridHash {
+    prefix: Vec<Vec<u64>>, // prefix[i][j] is hash of grid[..i][..j]
+    row_pow: Vec<u64>,
+    column_pow: Vec<u64>,
+}
+impl GridHash {
+    /// **O(hw)**, calculate 2-dimensional prefix hashes
+    pub fn new<T: Hash>(grid: &[Vec<T>]) -> Self {
+        let (row_base, column_base) = RANDOM_STATE.with(|state| (state.0, state.1));
+        let (h, w) = (grid.len(), grid.first().map_or(0, |row| row.len()));
+        let mut prefix = vec![vec![0; w + 1]; h + 1];
+        for i in 0..h {
+            for j in 0..w {
+                let above = mul_mod(prefix[i][j + 1], row_base);
+                let left = mul_mod(prefix[i + 1][j], column_base);
+                let diagonal = mul_mod(mul_mod(prefix[i][j], row_base), column_base);
+                prefix[i + 1][j + 1] =
+                    add_mod(sub_mod(add_mod(above, left), diagonal), element_hash(&grid[i][j]));
+            }
+        }
+        let row_pow =
+            (0..=h).scan(1, |p, _| Some(std::mem::replace(p, mul_mod(*p, row_base)))).collect();
+        let column_pow =
+            (0..=w).scan(1, |p, _| Some(std::mem::replace(p, mul_mod(*p, column_base)))).collect();
+        GridHash { prefix, row_pow, column_pow }
+    }
+
+    /// **O(1)**, hash of rectangle grid[rows][columns]
+    pub fn hash(&self, rows: Range<usize>, columns: Range<usize>) -> u64 {
+        let (i0, i1, j0, j1) = (rows.start, rows.end, columns.start, columns.end);
+        let (row_pow, column_pow) = (self.row_pow[i1 - i0], self.column_pow[j1 - j0]);
+        let whole = self.prefix[i1][j1];
+        let above = mul_mod(self.prefix[i0][j1], row_pow);
+        let left = mul_mod(self.prefix[i1][j0], column_pow);
+        let diagonal = mul_mod(mul_mod(self.prefix[i0][j0], row_pow), column_pow);
+        add_mod(sub_mod(sub_mod(whole, above), left), diagonal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mod_arithmetic() {
+        assert_eq!(mul_mod(MODULO - 1, MODULO - 1), 1);
+        assert_eq!(mul_mod(1 << 60, 4), 2);
+        assert_eq!(add_mod(MODULO - 1, 2), 1);
+        assert_eq!(sub_mod(1, 2), MODULO - 1);
+        assert_eq!(pow_mod(2, 61), 1);
+        assert_eq!(pow_mod(3, 0), 1);
+    }
+
+    #[test]
+    fn test_substring_hash() {
+        let s: Vec<_> = "abracadabra".chars().collect();
+        let rh = RollingHash::new(&s);
+        assert_eq!(rh.hash(0..4), rh.hash(7..11));
+        assert_eq!(rh.hash(0..4), rh.hash(7..));
+        assert_ne!(rh.hash(0..4), rh.hash(1..5));
+        assert_eq!(rh.hash(3..3), rh.hash(5..5));
+        let other = RollingHash::new(&"cadabra".chars().collect::<Vec<_>>());
+        assert_eq!(rh.hash(4..), other.hash(..));
+        assert_eq!(rh.key(4..), other.key(..));
+        assert_eq!(rh.key(0..1).concat(&rh.key(1..4)), rh.key(7..));
+        let custom = RollingHash::with_base(&s, 12345);
+        assert_eq!(custom.key(0..1).concat(&custom.key(1..4)), custom.key(7..));
+        assert_ne!(custom.key(0..4), rh.key(0..4));
+    }
+
+    #[test]
+    fn test_leading_zero() {
+        let (a, b) = (RollingHash::new(&[0u8, 1]), RollingHash::new(&[1u8]));
+        assert_ne!(a.hash(..), b.hash(..));
+        assert_ne!(a.key(..), b.key(..));
+    }
+
+    #[test]
+    fn test_lcp_compare() {
+        let s: Vec<_> = "abracadabra".chars().collect();
+        let rh = RollingHash::new(&s);
+        assert_eq!(rh.lcp(0, &rh, 7), 4);
+        assert_eq!(rh.lcp(0, &rh, 3), 1);
+        assert_eq!(rh.lcp(1, &rh, 2), 0);
+        assert_eq!(rh.lcp(0, &rh, 0), 11);
+        assert_eq!(rh.compare(0..4, &rh, 7..), Ordering::Equal);
+        assert_eq!(rh.compare(0.., &rh, 7..), Ordering::Greater);
+        assert_eq!(rh.compare(3.., &rh, 5..), Ordering::Less);
+        assert_eq!(rh.compare(1..2, &rh, 8..10), Ordering::Less);
+    }
+
+    #[test]
+    fn test_compare_naive() {
+        let mut rng = rand::rng();
+        let s: Vec<u32> = (0..60).map(|_| rng.random_range(0..2)).collect();
+        let rh = RollingHash::new(&s);
+        for _ in 0..500 {
+            let (a, b) = (rng.random_range(0..=60), rng.random_range(0..=60));
+            let (c, d) = (rng.random_range(0..=60), rng.random_range(0..=60));
+            let (r1, r2) = (a.min(b)..a.max(b), c.min(d)..c.max(d));
+            assert_eq!(rh.compare(r1.clone(), &rh, r2.clone()), s[r1.clone()].cmp(&s[r2.clone()]));
+            assert_eq!(rh.key(r1.clone()) == rh.key(r2.clone()), s[r1] == s[r2]);
+        }
+    }
+
+    #[test]
+    fn test_substring_key_map() {
+        use std::collections::HashMap;
+        let s: Vec<_> = "abababab".bytes().collect();
+        let rh = RollingHash::new(&s);
+        let mut counts = HashMap::new();
+        for i in 0..=s.len() - 3 {
+            *counts.entry(rh.key(i..i + 3)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&rh.key(0..3)], 3);
+        assert_eq!(counts[&rh.key(1..4)], 3);
+    }
+
+    #[test]
+    fn test_grid_hash() {
+        let grid: Vec<Vec<_>> =
+            ["abab", "baba", "abab"].iter().map(|r| r.chars().collect()).collect();
+        let gh = GridHash::new(&grid);
+        assert_eq!(gh.hash(0..2, 0..2), gh.hash(1..3, 1..3));
+        assert_eq!(gh.hash(0..2, 0..2), gh.hash(0..2, 2..4));
+        assert_ne!(gh.hash(0..2, 0..2), gh.hash(0..2, 1..3));
+        assert_eq!(gh.hash(0..1, 0..4), gh.hash(2..3, 0..4));
+        assert_eq!(gh.hash(1..1, 0..4), gh.hash(0..3, 2..2));
+        let pattern: Vec<Vec<_>> = ["ba", "ab"].iter().map(|r| r.chars().collect()).collect();
+        assert_eq!(GridHash::new(&pattern).hash(0..2, 0..2), gh.hash(1..3, 0..2));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::algo::{bisect::bisect, compress::coordinate_compress};
+
+/// **O(n + upper)**, suffix array of s whose elements are in 0..=upper, by SA-IS
+pub fn suffix_array_with_upper(s: &[usize], upper: usize) -> Vec<usize> {
+    let n = s.len();
+    match n {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => return if s[0] < s[1] { vec![0, 1] } else { vec![1, 0] },
+        _ => (),
+    }
+    // ls[i]: suffix i is S-type (smaller than suffix i+1) or not (L-type)
+    let mut ls = vec![false; n];
+    for i in (0..n - 1).rev() {
+        ls[i] = if s[i] == s[i + 1] { ls[i + 1] } else { s[i] < s[i + 1] };
+    }
+    // bucket of c is [sum_s[c], sum_l[c + 1]), L-type is placed at front and S-type is placed at back
+    let (mut sum_l, mut sum_s) = (vec![0; upper + 2], vec![0; upper + 1]);
+    for i in 0..n {
+        if !ls[i] {
+            sum_s[s[i]] += 1;
+        } else {
+            sum_l[s[i] + 1] += 1;
+        }
+    }
+    for c in 0..=upper {
+        sum_s[c] += sum_l[c];
+        sum_l[c + 1] += sum_s[c];
+    }
+    let (mut lms_map, mut lms) = (vec![usize::MAX; n + 1], Vec::new());
+    for i in 1..n {
+        if !ls[i - 1] && ls[i] {
+            lms_map[i] = lms.len();
+            lms.push(i);
+        }
+    }
+    let mut sa = vec![usize::MAX; n];
+    induce(s, &ls, &sum_s, &sum_l, &lms, &mut sa);
+    if !lms.is_empty() {
+        // name sorted LMS substrings and sort LMS suffixes recursively
+        let m = lms.len();
+        let sorted_lms: Vec<_> = sa.iter().cloned().filter(|&v| lms_map[v] != usize::MAX).collect();
+        let (mut rec_s, mut rec_upper) = (vec![0; m], 0);
+        for i in 1..m {
+            let (mut l, mut r) = (sorted_lms[i - 1], sorted_lms[i]);
+            let end_l = if lms_map[l] + 1 < m { lms[lms_map[l] + 1] } else { n };
+            let end_r = if lms_map[r] + 1 < m { lms[lms_map[r] + 1] } else { n };
+            let same = end_l - l == end_r - r && {
+                while l < end_l && s[l] == s[r] {
+                    l += 1;
+                    r += 1;
+                }
+                l != n && s[l] == s[r]
+            };
+            if !same {
+                rec_upper += 1;
+            }
+            rec_s[lms_map[sorted_lms[i]]] = rec_upper;
+        }
+        let rec_sa = suffix_array_with_upper(&rec_s, rec_upper);
+        let sorted_lms: Vec<_> = rec_sa.into_iter().map(|i| lms[i]).collect();
+        induce(s, &ls, &sum_s, &sum_l, &sorted_lms, &mut sa);
+    }
+    sa
+}
+
+/// **O(n)**, induced sort from sorted LMS suffixes
+fn induce(
+    s: &[usize],
+    ls: &[bool],
+    sum_s: &[usize],
+    sum_l: &[usize],
+    lms: &[usize],
+    sa: &mut [usize],
+) {
+    let n = s.len();
+    sa.iter_mut().for_each(|v| *v = usize::MAX);
+    let mut buf = sum_s.to_vec();
+    for &d in lms.iter().filter(|&&d| d != n) {
+        sa[buf[s[d]]] = d;
+        buf[s[d]] += 1;
+    }
+    buf.copy_from_slice(&sum_l[..sum_s.len()]);
+    sa[buf[s[n - 1]]] = n - 1;
+    buf[s[n - 1]] += 1;
+    for i in 0..n {
+        let v = sa[i];
+        if v != usize::MAX && v >= 1 && !ls[v - 1] {
+            sa[buf[s[v - 1]]] = v - 1;
+            buf[s[v - 1]] += 1;
+        }
+    }
+    let mut buf = sum_l.to_vec();
+    for i in (0..n).rev() {
+        let v = sa[i];
+        if v != usize::MAX && v >= 1 && ls[v - 1] {
+            buf[s[v - 1] + 1] -= 1;
+            sa[buf[s[v - 1] + 1]] = v - 1;
+        }
+    }
+}
+
+/// **O(n log(n))**, suffix array of s, sa[i] is starting position of i-th smallest suffix
+pub fn suffix_array<T: Ord + Copy>(s: &[T]) -> Vec<usize> {
+    let compressed = coordinate_compress(s);
+    let upper = compressed.iter().cloned().max().unwrap_or(0);
+    suffix_array_with_upper(&compressed, upper)
+}
+
+/// **O(n)**, lcp[i] is the length of longest common prefix of suffix sa[i] and sa[i+1], by Kasai
+pub fn lcp_array<T: Eq>(s: &[T], sa: &[usize]) -> Vec<usize> {
+    let n = s.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut rank = vec![0; n];
+    for (i, &p) in sa.iter().enumerate() {
+        rank[p] = i;
+    }
+    let (mut lcp, mut h) = (vec![0; n - 1], 0usize);
+    for i in 0..n {
+        h = h.saturating_sub(1);
+        if rank[i] == 0 {
+            continue;
+        }
+        let j = sa[rank[i] - 1];
+        while j + h < n && i + h < n && s[j + h] == s[i + h] {
+            h += 1;
+        }
+        lcp[rank[i] - 1] = h;
+    }
+    lcp
+}
+
+pub struct SuffixArray<T> {
+    s: Vec<T>,
+    sa: Vec<usize>,
+    rank: Vec<usize>,
+    lcp: Vec<usize>,
+    sparse_table: Vec<Vec<usize>>, // sparse_table[k][i] = min(lcp[i..i + 2^k])
+}
+impl<T: Ord + Copy> SuffixArray<T> {
+    /// **O(n log(n))**, build suffix array, lcp array and sparse table of lcp
+    pub fn new(s: &[T]) -> Self {
+        let sa = suffix_array(s);
+        let lcp = lcp_array(s, &sa);
+        let mut rank = vec![0; s.len()];
+        for (i, &p) in sa.iter().enumerate() {
+            rank[p] = i;
+        }
+        let mut sparse_table = vec![lcp.clone()];
+        while 1 << sparse_table.len() <= lcp.len() {
+            let (prev, width) =
+                (&sparse_table[sparse_table.len() - 1], 1 << (sparse_table.len() - 1));
+            let next = (0..prev.len() - width).map(|i| prev[i].min(prev[i + width])).collect();
+            sparse_table.push(next);
+        }
+        SuffixArray { s: s.to_vec(), sa, rank, lcp, sparse_table }
+    }
+
+    /// **O(1)**, suffix array
+    pub fn sa(&self) -> &[usize] {
+        &self.sa
+    }
+
+    /// **O(1)**, rank[i] is the position of suffix i in suffix array
+    pub fn rank(&self) -> &[usize] {
+        &self.rank
+    }
+
+    /// **O(1)**, lcp array
+    pub fn lcp(&self) -> &[usize] {
+        &self.lcp
+    }
+
+    /// **O(1)**, length of longest common prefix of suffix i and suffix j
+    pub fn lcp_of(&self, i: usize, j: usize) -> usize {
+        if i == j {
+            return self.s.len() - i;
+        }
+        let (l, r) = (self.rank[i].min(self.rank[j]), self.rank[i].max(self.rank[j]));
+        let k = (usize::BITS - 1 - (r - l).leading_zeros()) as usize;
+        self.sparse_table[k][l].min(self.sparse_table[k][r - (1 << k)])
+    }
+
+    /// **O(n)**, the number of distinct non-empty substrings
+    pub fn distinct_substrings(&self) -> usize {
+        let n = self.s.len();
+        n * (n + 1) / 2 - self.lcp.iter().sum::<usize>()
+    }
+
+    /// **O(n)**, longest substring that occur at least twice (may overlap), empty if there is no such
+    pub fn longest_repeated_substring(&self) -> &[T] {
+        match self.lcp.iter().enumerate().max_by_key(|&(_, &h)| h) {
+            Some((i, &h)) => &self.s[self.sa[i]..self.sa[i] + h],
+            None => &[],
+        }
+    }
+
+    /// **O(m log(n))**, range of suffix array whose suffixes start with pattern
+    pub fn occurrence_range(&self, pattern: &[T]) -> std::ops::Range<usize> {
+        let prefix = |i: usize| &self.s[self.sa[i]..(self.sa[i] + pattern.len()).min(self.s.len())];
+        let lower = self.partition_point(|i| prefix(i) >= pattern);
+        let upper = self.partition_point(|i| prefix(i) > pattern);
+        lower..upper
+    }
+
+    /// **O(m log(n) + k)**, all starting positions of pattern in ascending order
+    pub fn find_all(&self, pattern: &[T]) -> Vec<usize> {
+        let mut positions = self.sa[self.occurrence_range(pattern)].to_vec();
+        positions.sort();
+        positions
+    }
+
+    /// **O(log(n))**, first index of suffix array that f(index) is true (f should be monotone)
+    fn partition_point<F: Fn(usize) -> bool>(&self, f: F) -> usize {
+        let n = self.sa.len();
+        if n == 0 || f(0) {
+            0
+        } else if !f(n - 1) {
+            n
+        } else {
+            bisect(0..n, |&i| f(i)).expect("f(0) is false and f(n - 1) is true")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_suffix_array<T: Ord>(s: &[T]) -> Vec<usize> {
+        let mut sa: Vec<_> = (0..s.len()).collect();
+        sa.sort_by(|&a, &b| s[a..].cmp(&s[b..]));
+        sa
+    }
+
+    #[test]
+    fn test_suffix_array() {
+        assert_eq!(suffix_array(b"abracadabra"), [10, 7, 0, 3, 5, 8, 1, 4, 6, 9, 2]);
+        assert_eq!(suffix_array(b"mississippi"), [10, 7, 4, 1, 0, 9, 8, 6, 3, 5, 2]);
+        assert_eq!(suffix_array(&[1, 1, 1, 1]), [3, 2, 1, 0]);
+        assert_eq!(suffix_array(&[0; 0]), []);
+        assert_eq!(suffix_array(&[5]), [0]);
+    }
+
+    #[test]
+    fn test_suffix_array_naive() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..300 {
+            let upper = rng.random_range(0..4);
+            let s: Vec<usize> =
+                (0..rng.random_range(0..50)).map(|_| rng.random_range(0..=upper)).collect();
+            let sa = suffix_array_with_upper(&s, upper);
+            assert_eq!(sa, naive_suffix_array(&s));
+            let naive_lcp: Vec<_> = sa
+                .windows(2)
+                .map(|w| s[w[0]..].iter().zip(s[w[1]..].iter()).take_while(|(a, b)| a == b).count())
+                .collect();
+            assert_eq!(lcp_array(&s, &sa), naive_lcp);
+        }
+    }
+
+    #[test]
+    fn test_lcp_of() {
+        let s = b"abracadabra";
+        let sa = SuffixArray::new(s);
+        assert_eq!(sa.lcp(), [1, 4, 1, 1, 0, 3, 0, 0, 0, 2]);
+        for i in 0..s.len() {
+            for j in 0..s.len() {
+                let naive = s[i..].iter().zip(s[j..].iter()).take_while(|(a, b)| a == b).count();
+                assert_eq!(sa.lcp_of(i, j), naive);
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_helpers() {
+        let sa = SuffixArray::new(b"abracadabra");
+        assert_eq!(sa.longest_repeated_substring(), b"abra");
+        assert_eq!(sa.find_all(b"abra"), [0, 7]);
+        assert_eq!(sa.find_all(b"a"), [0, 3, 5, 7, 10]);
+        assert_eq!(sa.find_all(b"ac"), [3]);
+        assert_eq!(sa.find_all(b"x"), []);
+        assert_eq!(sa.find_all(b"abracadabrax"), []);
+        assert_eq!(sa.occurrence_range(b"bra").len(), 2);
+        let sa = SuffixArray::new(&[1, 2, 3]);
+        assert_eq!(sa.longest_repeated_substring(), []);
+        assert_eq!(sa.distinct_substrings(), 6);
+        let sa = SuffixArray::new(b"aaa");
+        assert_eq!(sa.distinct_substrings(), 3);
+        assert_eq!(sa.longest_repeated_substring(), b"aa");
+    }
+
+    #[test]
+    fn test_distinct_substrings_naive() {
+        use rand::RngExt;
+        use std::collections::HashSet;
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let s: Vec<u8> = (0..rng.random_range(0..30)).map(|_| rng.random_range(0..3)).collect();
+            let naive: HashSet<_> = (0..s.len())
+                .flat_map(|i| (i + 1..=s.len()).map(move |j| (i, j)))
+                .map(|(i, j)| &s[i..j])
+                .collect();
+            assert_eq!(SuffixArray::new(&s).distinct_substrings(), naive.len());
+        }
+    }
+}
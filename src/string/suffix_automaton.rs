@@ -0,0 +1,369 @@
+use std::{
+    cell::OnceCell,
+    collections::{BTreeMap, VecDeque},
+    ops::Range,
+};
+
+type StateId = usize;
+
+#[derive(Debug, Clone)]
+struct State<T> {
+    next: BTreeMap<T, StateId>,
+    link: Option<StateId>, // state of the longest suffix that belongs to other state
+    len: usize,            // length of the longest substring of this state
+    is_clone: bool,
+    first_end: usize, // end (exclusive) of the first occurrence
+}
+
+/// minimum automaton that accepts all suffixes, state 0 is root (empty string),
+/// each state is a set of substrings that have the same end positions
+#[derive(Debug, Clone)]
+pub struct SuffixAutomaton<T> {
+    states: Vec<State<T>>,
+    last: StateId,
+    s_len: usize,
+    distinct: usize,
+    // tables for queries, calculated at the first query after the last push
+    occurrence_counts: OnceCell<Vec<usize>>,
+    path_counts: OnceCell<Vec<usize>>,
+}
+impl<T> Default for SuffixAutomaton<T>
+where
+    T: Ord + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SuffixAutomaton<T>
+where
+    T: Ord + Clone,
+{
+    /// **O(1)**, make automaton of empty string
+    pub fn new() -> Self {
+        let root =
+            State { next: BTreeMap::new(), link: None, len: 0, is_clone: false, first_end: 0 };
+        SuffixAutomaton {
+            states: vec![root],
+            last: 0,
+            s_len: 0,
+            distinct: 0,
+            occurrence_counts: OnceCell::new(),
+            path_counts: OnceCell::new(),
+        }
+    }
+
+    /// **O(n log(σ))**, build automaton of s
+    pub fn build(s: &[T]) -> Self {
+        let mut sa = Self::new();
+        for x in s {
+            sa.push(x.clone());
+        }
+        sa
+    }
+
+    /// **O(log(σ))** amortized, append x to the string
+    pub fn push(&mut self, x: T) {
+        self.occurrence_counts.take();
+        self.path_counts.take();
+        self.s_len += 1;
+        let current = self.states.len();
+        self.states.push(State {
+            next: BTreeMap::new(),
+            link: None,
+            len: self.s_len,
+            is_clone: false,
+            first_end: self.s_len,
+        });
+        let mut p = Some(self.last);
+        while let Some(v) = p {
+            if self.states[v].next.contains_key(&x) {
+                break;
+            }
+            self.states[v].next.insert(x.clone(), current);
+            p = self.states[v].link;
+        }
+        let link = match p {
+            None => 0,
+            Some(p) => {
+                let q = self.states[p].next[&x];
+                if self.states[p].len + 1 == self.states[q].len {
+                    q
+                } else {
+                    // split q, so that the state of length len(p) + 1 exist
+                    let clone = self.states.len();
+                    let state = State {
+                        len: self.states[p].len + 1,
+                        is_clone: true,
+                        ..self.states[q].clone()
+                    };
+                    self.states.push(state);
+                    let mut p = Some(p);
+                    while let Some(v) = p {
+                        match self.states[v].next.get_mut(&x) {
+                            Some(next) if *next == q => *next = clone,
+                            _ => break,
+                        }
+                        p = self.states[v].link;
+                    }
+                    self.states[q].link = Some(clone);
+                    clone
+                }
+            }
+        };
+        self.states[current].link = Some(link);
+        self.distinct += self.states[current].len - self.states[link].len;
+        self.last = current;
+    }
+
+    /// **O(1)**, return the number of states
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// **O(1)**, return true if the string is empty, else return false
+    pub fn is_empty(&self) -> bool {
+        self.s_len == 0
+    }
+
+    /// **O(m log(σ))**, state that accept pattern
+    fn state_of(&self, pattern: &[T]) -> Option<StateId> {
+        pattern.iter().try_fold(0, |v, x| self.states[v].next.get(x).cloned())
+    }
+
+    /// **O(m log(σ))**, judge pattern is substring or not
+    pub fn contains(&self, pattern: &[T]) -> bool {
+        self.state_of(pattern).is_some()
+    }
+
+    /// **O(m log(σ))**, range of the first occurrence of pattern
+    pub fn first_occurrence(&self, pattern: &[T]) -> Option<Range<usize>> {
+        self.state_of(pattern)
+            .map(|v| self.states[v].first_end - pattern.len()..self.states[v].first_end)
+    }
+
+    /// **O(n)**, state ids sorted by the length in descending order, which is topological order of suffix link
+    fn states_by_len_desc(&self) -> Vec<StateId> {
+        let mut buckets = vec![0; self.s_len + 2];
+        for state in &self.states {
+            buckets[self.s_len - state.len + 1] += 1;
+        }
+        for l in 1..buckets.len() {
+            buckets[l] += buckets[l - 1];
+        }
+        let mut order = vec![0; self.states.len()];
+        for (v, state) in self.states.iter().enumerate() {
+            let b = &mut buckets[self.s_len - state.len];
+            order[*b] = v;
+            *b += 1;
+        }
+        order
+    }
+
+    /// **O(n)** at the first call after push else **O(1)**,
+    /// counts[state] is the number of occurrences of substrings that belong to the state
+    pub fn occurrence_counts(&self) -> &[usize] {
+        self.occurrence_counts.get_or_init(|| {
+            let mut counts: Vec<_> =
+                self.states.iter().map(|state| if state.is_clone { 0 } else { 1 }).collect();
+            for v in self.states_by_len_desc() {
+                if let Some(link) = self.states[v].link {
+                    counts[link] += counts[v];
+                }
+            }
+            counts
+        })
+    }
+
+    /// **O(m log(σ))** after occurrence_counts is calculated, the number of occurrences of pattern,
+    /// empty pattern occurs n + 1 times
+    pub fn count_occurrences(&self, pattern: &[T]) -> usize {
+        self.state_of(pattern).map_or(0, |v| self.occurrence_counts()[v])
+    }
+
+    /// **O(nσ)** at the first call after push else **O(1)**,
+    /// paths[state] is the number of distinct strings (including empty) that start from the state
+    fn path_counts(&self) -> &[usize] {
+        self.path_counts.get_or_init(|| {
+            let mut paths = vec![1; self.states.len()];
+            for v in self.states_by_len_desc() {
+                paths[v] += self.states[v].next.values().map(|&u| paths[u]).sum::<usize>();
+            }
+            paths
+        })
+    }
+
+    /// **O(1)**, the number of distinct non-empty substrings
+    pub fn distinct_substrings(&self) -> usize {
+        self.distinct
+    }
+
+    /// **O(|answer| σ)** after path counts are calculated in **O(nσ)** once,
+    /// k-th (0-indexed) lexicographically smallest distinct non-empty substring
+    pub fn kth_substring(&self, mut k: usize) -> Option<Vec<T>> {
+        if k >= self.distinct {
+            return None;
+        }
+        let paths = self.path_counts();
+        let (mut v, mut substring) = (0, Vec::new());
+        loop {
+            for (x, &u) in &self.states[v].next {
+                if k < paths[u] {
+                    substring.push(x.clone());
+                    v = u;
+                    break;
+                }
+                k -= paths[u];
+            }
+            if k == 0 {
+                return Some(substring);
+            }
+            // skip the string that end at v
+            k -= 1;
+        }
+    }
+
+    /// **O(m log(σ))**, range in other of the longest common substring of the string and other
+    pub fn longest_common_substring(&self, other: &[T]) -> Range<usize> {
+        let (mut v, mut len, mut best) = (0, 0, 0..0);
+        for (i, x) in other.iter().enumerate() {
+            while v != 0 && !self.states[v].next.contains_key(x) {
+                v = self.states[v].link.expect("non-root state has suffix link");
+                len = self.states[v].len;
+            }
+            if let Some(&u) = self.states[v].next.get(x) {
+                v = u;
+                len += 1;
+            }
+            if len > best.len() {
+                best = i + 1 - len..i + 1;
+            }
+        }
+        best
+    }
+
+    /// **O(nσ log(σ))**, the shortest string over alphabet that is not substring,
+    /// lexicographically smallest one if alphabet is sorted
+    pub fn shortest_absent(&self, alphabet: &[T]) -> Option<Vec<T>> {
+        // parents[state] is (previous state, element) on the shortest path from root
+        let mut parents = vec![None; self.states.len()];
+        let mut visited = vec![false; self.states.len()];
+        let mut queue: VecDeque<_> = vec![0].into_iter().collect();
+        visited[0] = true;
+        while let Some(v) = queue.pop_front() {
+            for x in alphabet {
+                match self.states[v].next.get(x) {
+                    None => {
+                        let (mut absent, mut current) = (vec![x.clone()], v);
+                        while let Some((prev, y)) = parents[current].clone() {
+                            absent.push(y);
+                            current = prev;
+                        }
+                        absent.reverse();
+                        return Some(absent);
+                    }
+                    Some(&u) if !visited[u] => {
+                        visited[u] = true;
+                        parents[u] = Some((v, x.clone()));
+                        queue.push_back(u);
+                    }
+                    Some(_) => (),
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn substrings<T: Ord + Clone>(s: &[T]) -> BTreeSet<Vec<T>> {
+        (0..s.len()).flat_map(|l| (l + 1..=s.len()).map(move |r| s[l..r].to_vec())).collect()
+    }
+
+    #[test]
+    fn test_suffix_automaton() {
+        let s: Vec<_> = "abcbc".chars().collect();
+        let sa = SuffixAutomaton::build(&s);
+        assert_eq!(sa.distinct_substrings(), 12);
+        assert!(sa.contains(&['c', 'b']));
+        assert!(!sa.contains(&['c', 'a']));
+        assert_eq!(sa.count_occurrences(&['b', 'c']), 2);
+        assert_eq!(sa.count_occurrences(&['a', 'c']), 0);
+        assert_eq!(sa.count_occurrences(&[]), 6);
+        assert_eq!(sa.first_occurrence(&['c', 'b']), Some(2..4));
+        assert_eq!(sa.kth_substring(0), Some(vec!['a']));
+        assert_eq!(sa.kth_substring(5), Some(vec!['b']));
+        assert_eq!(sa.kth_substring(11), Some(vec!['c', 'b', 'c']));
+        assert_eq!(sa.kth_substring(12), None);
+        assert_eq!(sa.state_count(), 8);
+    }
+
+    #[test]
+    fn test_push_after_query() {
+        let mut sa = SuffixAutomaton::build(b"ab");
+        assert_eq!(sa.count_occurrences(b"b"), 1);
+        assert_eq!(sa.kth_substring(2), Some(b"b".to_vec()));
+        sa.push(b'a');
+        sa.push(b'b');
+        assert_eq!(sa.count_occurrences(b"b"), 2);
+        assert_eq!(sa.kth_substring(2), Some(b"aba".to_vec()));
+    }
+
+    #[test]
+    fn test_longest_common_substring() {
+        let sa = SuffixAutomaton::build(b"xabcdy");
+        assert_eq!(sa.longest_common_substring(b"zzbcdzabcz"), 2..5);
+        assert_eq!(sa.longest_common_substring(b"bcdy"), 0..4);
+        assert_eq!(sa.longest_common_substring(b"qqq"), 0..0);
+    }
+
+    #[test]
+    fn test_shortest_absent() {
+        let sa = SuffixAutomaton::build(b"aabba");
+        assert_eq!(sa.shortest_absent(b"ab"), Some(b"aaa".to_vec()));
+        assert_eq!(sa.shortest_absent(b"abc"), Some(b"c".to_vec()));
+        let sa = SuffixAutomaton::build(b"aabb");
+        assert_eq!(sa.shortest_absent(b"ab"), Some(b"ba".to_vec()));
+        assert_eq!(SuffixAutomaton::build(b"").shortest_absent(b"ab"), Some(b"a".to_vec()));
+        assert_eq!(sa.shortest_absent(b""), None);
+    }
+
+    #[test]
+    fn test_random_naive() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let s: Vec<u8> = (0..rng.random_range(0..20)).map(|_| rng.random_range(0..3)).collect();
+            let sa = SuffixAutomaton::build(&s);
+            let all = substrings(&s);
+            assert_eq!(sa.distinct_substrings(), all.len());
+            for (k, sub) in all.iter().enumerate() {
+                assert_eq!(sa.kth_substring(k).as_ref(), Some(sub));
+                let count = s.windows(sub.len()).filter(|w| w == sub).count();
+                assert_eq!(sa.count_occurrences(sub), count);
+                let first = s.windows(sub.len()).position(|w| w == sub).unwrap();
+                assert_eq!(sa.first_occurrence(sub), Some(first..first + sub.len()));
+            }
+            let t: Vec<u8> = (0..rng.random_range(0..20)).map(|_| rng.random_range(0..3)).collect();
+            let common = substrings(&t).intersection(&all).map(|sub| sub.len()).max().unwrap_or(0);
+            let range = sa.longest_common_substring(&t);
+            assert_eq!(range.len(), common);
+            assert!(sa.contains(&t[range]));
+            let absent = sa.shortest_absent(&[0, 1, 2]).unwrap();
+            assert!(!sa.contains(&absent));
+            let shorter = (1..absent.len()).all(|len| {
+                (0..3usize.pow(len as u32)).all(|x| {
+                    let candidate: Vec<u8> =
+                        (0..len).map(|i| (x / 3usize.pow(i as u32) % 3) as u8).collect();
+                    sa.contains(&candidate)
+                })
+            });
+            assert!(shorter);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+/// **O(n)**, z[i] is the length of longest common prefix of s and s[i..]
+pub fn z_algorithm<T: Eq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut z = vec![0; n];
+    if n == 0 {
+        return z;
+    }
+    z[0] = n;
+    // [left, right) is the rightmost segment that match prefix of s
+    let (mut left, mut right) = (0, 0);
+    for i in 1..n {
+        if i < right {
+            z[i] = z[i - left].min(right - i);
+        }
+        while i + z[i] < n && s[z[i]] == s[i + z[i]] {
+            z[i] += 1;
+        }
+        if i + z[i] > right {
+            left = i;
+            right = i + z[i];
+        }
+    }
+    z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_z_algorithm() {
+        let s: Vec<_> = "aaabaaaab".chars().collect();
+        assert_eq!(z_algorithm(&s), [9, 2, 1, 0, 3, 4, 2, 1, 0]);
+        let s: Vec<_> = "abacaba".chars().collect();
+        assert_eq!(z_algorithm(&s), [7, 0, 1, 0, 3, 0, 1]);
+        assert_eq!(z_algorithm(&[1, 1, 1, 1]), [4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_z_algorithm_bound() {
+        assert_eq!(z_algorithm(&[0; 0]), []);
+        assert_eq!(z_algorithm(b"x"), [1]);
+    }
+
+    #[test]
+    fn test_z_algorithm_naive() {
+        use rand::RngExt;
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let s: Vec<u8> = (0..rng.random_range(0..30)).map(|_| rng.random_range(0..2)).collect();
+            let naive: Vec<_> = (0..s.len())
+                .map(|i| s.iter().zip(s[i..].iter()).take_while(|(a, b)| a == b).count())
+                .collect();
+            assert_eq!(z_algorithm(&s), naive);
+        }
+    }
+}